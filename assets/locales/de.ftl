@@ -0,0 +1,143 @@
+language-name = Deutsch
+
+title = Minesweeper
+begin = Start
+daily-challenge = Tagesaufgabe, Serie { $streak } (Rekord { $best })
+daily-practice = Tagesübung, Serie { $streak } (Rekord { $best })
+settings = Einstellungen
+reset = Zurücksetzen
+back = Zurück
+off = Aus
+on = An
+
+size = Größe:{" "}
+size-small = Klein
+size-medium = Mittel
+size-big = Groß
+size-huge = Riesig
+size-giant = Gigantisch
+size-colossal = Kolossal
+size-endless = Endlos
+size-custom = Eigenes
+
+difficulty = Schwierigkeit:{" "}
+difficulty-easy = Leicht
+difficulty-medium = Mittel
+difficulty-hard = Schwer
+difficulty-extreme = Extrem
+difficulty-debug = Debug
+
+mode = Modus:{" "}
+mode-solo = Solo
+mode-race = Wettrennen
+mode-flags = Flaggen
+
+time = { $hours ->
+    [0] { $minutes ->
+        [0] { $seconds }
+       *[other] { $minutes }:{ NUMBER($seconds, minimumIntegerDigits: 2) }
+    }
+   *[other] { $hours }:{ NUMBER($minutes, minimumIntegerDigits: 2) }:{ NUMBER($seconds, minimumIntegerDigits: 2) }
+}
+
+hud-mines = Minen: { $count }
+hud-score = Punkte: { $score }
+hud-time = Zeit: { $time }
+hud-game-over = { $menu }: Menü, { $restart }: Neustart
+hud-revealed = Aufgedeckt: { $revealed }/{ $safe }
+hud-stats =
+    Klicks: { $left } links, { $right } rechts, { $chords ->
+        [one] { $chords } Akkord
+       *[other] { $chords } Akkorde
+    }
+    { $progress }, { $flags ->
+        [one] { $flags } Flagge
+       *[other] { $flags } Flaggen
+    }
+hud-pause = { $pause }: Fortsetzen, { $menu }: Menü
+hud-paused = Pausiert
+race-won = Gewonnen! { $menu }: Menü, { $restart }: Neustart
+race-lost = Gegner hat gewonnen. { $menu }: Menü, { $restart }: Neustart
+race-eliminated = Ausgeschieden! { $menu }: Menü, { $restart }: Neustart
+flags-score = Spieler { $player }: { $score }
+flags-turn = > Spieler { $player }: { $score } <
+flags-won = Spieler { $player } gewinnt { $score } zu { $other }! { $menu }: Menü, { $restart }: Neustart
+flags-tie = Unentschieden, { $score } zu { $other }! { $menu }: Menü, { $restart }: Neustart
+
+settings-binding = { $action }: { $binding }
+settings-listening = { $action }: Taste, Maustaste oder zwei Maustasten drücken...
+settings-theme = Farbschema: { $name }
+settings-tiles = Kacheln: { $name }
+settings-sounds = Klänge: { $name }
+settings-language = Sprache: { $name }
+
+handedness = Maus:{" "}
+handedness-right = Rechtshänder
+handedness-left = Linkshänder
+chord-style = Akkord:{" "}
+chord-style-both = Beide Tasten
+chord-style-middle = Mittelklick
+chord-style-custom = Eigene
+flag-chord = Flaggen-Akkord:{" "}
+colorblind = Farbenblind:{" "}
+colorblind-protanopia = Protanopie
+colorblind-deuteranopia = Deuteranopie
+colorblind-tritanopia = Tritanopie
+shape-cues = Formhinweise:{" "}
+reduce-motion = Weniger Bewegung:{" "}
+volume = Lautstärke:{" "}
+volume-percent = { $percent } %
+long-press = Langes Drücken:{" "}
+long-press-seconds = { $seconds } s
+mute = Stumm:{" "}
+
+action-reveal = Aufdecken
+action-flag = Flagge
+action-chord = Akkord
+action-pan = Verschieben
+action-zoom-in = Vergrößern
+action-zoom-out = Verkleinern
+action-restart = Neustart
+action-menu = Menü
+action-pause = Pause
+action-hint = Hinweis
+action-tiles = Kacheln
+action-export = Brett exportieren
+
+action-cursor-up = Cursor hoch
+action-cursor-down = Cursor runter
+action-cursor-left = Cursor links
+action-cursor-right = Cursor rechts
+action-cursor-reveal = Am Cursor aufdecken
+action-cursor-flag = Am Cursor markieren
+
+mouse-left = Links
+mouse-right = Rechts
+mouse-middle = Mitte
+mouse-back = Zurück
+mouse-forward = Vor
+mouse-other = Taste { $number }
+binding-click = { $button }klick
+binding-pair = { $first }+{ $second }-Klick
+
+announce-opened = { $count ->
+    [one] { $count } Feld aufgedeckt
+   *[other] { $count } Felder aufgedeckt
+}
+announce-flagged = Flagge gesetzt bei Spalte { $column } Zeile { $row }
+announce-unflagged = Flagge entfernt bei Spalte { $column } Zeile { $row }
+announce-mines = { $mines ->
+    [one] { $mines } Mine übrig
+   *[other] { $mines } Minen übrig
+}
+announce-joined = { $first }, { $second }
+announce-won = Gewonnen in { $time }
+announce-lost = Verloren, Mine bei Spalte { $column } Zeile { $row }
+announce-tile = Spalte { $column } Zeile { $row }: { $tile }
+
+tile-covered = verdeckt
+tile-flagged = Flagge
+tile-empty = leer
+tile-number = { $count }
+tile-mine = Mine
+tile-wrong-flag = falsche Flagge
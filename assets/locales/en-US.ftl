@@ -0,0 +1,143 @@
+language-name = English
+
+title = Minesweeper
+begin = Begin
+daily-challenge = Daily challenge, streak { $streak } (best { $best })
+daily-practice = Daily practice, streak { $streak } (best { $best })
+settings = Settings
+reset = Reset
+back = Back
+off = Off
+on = On
+
+size = Size:{" "}
+size-small = Small
+size-medium = Medium
+size-big = Big
+size-huge = Huge
+size-giant = Giant
+size-colossal = Colossal
+size-endless = Endless
+size-custom = Custom
+
+difficulty = Difficulty:{" "}
+difficulty-easy = Easy
+difficulty-medium = Medium
+difficulty-hard = Hard
+difficulty-extreme = Extreme
+difficulty-debug = Debug
+
+mode = Mode:{" "}
+mode-solo = Solo
+mode-race = Race
+mode-flags = Flags
+
+time = { $hours ->
+    [0] { $minutes ->
+        [0] { $seconds }
+       *[other] { $minutes }:{ NUMBER($seconds, minimumIntegerDigits: 2) }
+    }
+   *[other] { $hours }:{ NUMBER($minutes, minimumIntegerDigits: 2) }:{ NUMBER($seconds, minimumIntegerDigits: 2) }
+}
+
+hud-mines = Mines: { $count }
+hud-score = Score: { $score }
+hud-time = Time: { $time }
+hud-game-over = { $menu }: Menu, { $restart }: Restart
+hud-revealed = Revealed: { $revealed }/{ $safe }
+hud-stats =
+    Clicks: { $left } left, { $right } right, { $chords ->
+        [one] { $chords } chord
+       *[other] { $chords } chords
+    }
+    { $progress }, { $flags ->
+        [one] { $flags } flag
+       *[other] { $flags } flags
+    }
+hud-pause = { $pause }: Resume, { $menu }: Menu
+hud-paused = Paused
+race-won = You won! { $menu }: Menu, { $restart }: Restart
+race-lost = Opponent won. { $menu }: Menu, { $restart }: Restart
+race-eliminated = Eliminated! { $menu }: Menu, { $restart }: Restart
+flags-score = Player { $player }: { $score }
+flags-turn = > Player { $player }: { $score } <
+flags-won = Player { $player } wins { $score } to { $other }! { $menu }: Menu, { $restart }: Restart
+flags-tie = Draw, { $score } to { $other }! { $menu }: Menu, { $restart }: Restart
+
+settings-binding = { $action }: { $binding }
+settings-listening = { $action }: press a key, button or two buttons...
+settings-theme = Theme: { $name }
+settings-tiles = Tiles: { $name }
+settings-sounds = Sounds: { $name }
+settings-language = Language: { $name }
+
+handedness = Mouse:{" "}
+handedness-right = Right handed
+handedness-left = Left handed
+chord-style = Chord:{" "}
+chord-style-both = Both buttons
+chord-style-middle = Middle click
+chord-style-custom = Custom
+flag-chord = Flag chord:{" "}
+colorblind = Colorblind:{" "}
+colorblind-protanopia = Protanopia
+colorblind-deuteranopia = Deuteranopia
+colorblind-tritanopia = Tritanopia
+shape-cues = Shape cues:{" "}
+reduce-motion = Reduce motion:{" "}
+volume = Volume:{" "}
+volume-percent = { $percent }%
+long-press = Long press:{" "}
+long-press-seconds = { $seconds } s
+mute = Mute:{" "}
+
+action-reveal = Reveal
+action-flag = Flag
+action-chord = Chord
+action-pan = Pan
+action-zoom-in = Zoom in
+action-zoom-out = Zoom out
+action-restart = Restart
+action-menu = Menu
+action-pause = Pause
+action-hint = Hint
+action-tiles = Tiles
+action-export = Export board
+
+action-cursor-up = Cursor up
+action-cursor-down = Cursor down
+action-cursor-left = Cursor left
+action-cursor-right = Cursor right
+action-cursor-reveal = Reveal at cursor
+action-cursor-flag = Flag at cursor
+
+mouse-left = Left
+mouse-right = Right
+mouse-middle = Middle
+mouse-back = Back
+mouse-forward = Forward
+mouse-other = Button { $number }
+binding-click = { $button } Click
+binding-pair = { $first }+{ $second } Click
+
+announce-opened = { $count ->
+    [one] { $count } tile opened
+   *[other] { $count } tiles opened
+}
+announce-flagged = Flagged column { $column } row { $row }
+announce-unflagged = Unflagged column { $column } row { $row }
+announce-mines = { $mines ->
+    [one] { $mines } mine remaining
+   *[other] { $mines } mines remaining
+}
+announce-joined = { $first }, { $second }
+announce-won = Game won in { $time }
+announce-lost = Game lost, mine at column { $column } row { $row }
+announce-tile = Column { $column } row { $row }: { $tile }
+
+tile-covered = covered
+tile-flagged = flagged
+tile-empty = empty
+tile-number = { $count }
+tile-mine = mine
+tile-wrong-flag = wrong flag
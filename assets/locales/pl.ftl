@@ -0,0 +1,147 @@
+language-name = Polski
+
+title = Saper
+begin = Rozpocznij
+daily-challenge = Wyzwanie dnia, seria { $streak } (najlepsza { $best })
+daily-practice = Trening dnia, seria { $streak } (najlepsza { $best })
+settings = Ustawienia
+reset = Przywróć
+back = Wróć
+off = Wył.
+on = Wł.
+
+size = Rozmiar:{" "}
+size-small = Mały
+size-medium = Średni
+size-big = Duży
+size-huge = Ogromny
+size-giant = Gigantyczny
+size-colossal = Kolosalny
+size-endless = Nieskończony
+size-custom = Własny
+
+difficulty = Poziom:{" "}
+difficulty-easy = Łatwy
+difficulty-medium = Średni
+difficulty-hard = Trudny
+difficulty-extreme = Ekstremalny
+difficulty-debug = Debug
+
+mode = Tryb:{" "}
+mode-solo = Solo
+mode-race = Wyścig
+mode-flags = Flagi
+
+time = { $hours ->
+    [0] { $minutes ->
+        [0] { $seconds }
+       *[other] { $minutes }:{ NUMBER($seconds, minimumIntegerDigits: 2) }
+    }
+   *[other] { $hours }:{ NUMBER($minutes, minimumIntegerDigits: 2) }:{ NUMBER($seconds, minimumIntegerDigits: 2) }
+}
+
+hud-mines = Miny: { $count }
+hud-score = Wynik: { $score }
+hud-time = Czas: { $time }
+hud-game-over = { $menu }: Menu, { $restart }: Od nowa
+hud-revealed = Odkryte: { $revealed }/{ $safe }
+hud-stats =
+    Kliknięcia: { $left } lewym, { $right } prawym, { $chords ->
+        [one] { $chords } akord
+        [few] { $chords } akordy
+       *[many] { $chords } akordów
+    }
+    { $progress }, { $flags ->
+        [one] { $flags } flaga
+        [few] { $flags } flagi
+       *[many] { $flags } flag
+    }
+hud-pause = { $pause }: Wznów, { $menu }: Menu
+hud-paused = Wstrzymano
+race-won = Wygrana! { $menu }: Menu, { $restart }: Od nowa
+race-lost = Przeciwnik wygrał. { $menu }: Menu, { $restart }: Od nowa
+race-eliminated = Odpadasz! { $menu }: Menu, { $restart }: Od nowa
+flags-score = Gracz { $player }: { $score }
+flags-turn = > Gracz { $player }: { $score } <
+flags-won = Gracz { $player } wygrywa { $score } do { $other }! { $menu }: Menu, { $restart }: Od nowa
+flags-tie = Remis, { $score } do { $other }! { $menu }: Menu, { $restart }: Od nowa
+
+settings-binding = { $action }: { $binding }
+settings-listening = { $action }: naciśnij klawisz, przycisk lub dwa przyciski...
+settings-theme = Motyw: { $name }
+settings-tiles = Kafelki: { $name }
+settings-sounds = Dźwięki: { $name }
+settings-language = Język: { $name }
+
+handedness = Mysz:{" "}
+handedness-right = Praworęczna
+handedness-left = Leworęczna
+chord-style = Akord:{" "}
+chord-style-both = Oba przyciski
+chord-style-middle = Środkowy przycisk
+chord-style-custom = Własny
+flag-chord = Akord flagą:{" "}
+colorblind = Daltonizm:{" "}
+colorblind-protanopia = Protanopia
+colorblind-deuteranopia = Deuteranopia
+colorblind-tritanopia = Tritanopia
+shape-cues = Wzory kształtów:{" "}
+reduce-motion = Ogranicz ruch:{" "}
+volume = Głośność:{" "}
+volume-percent = { $percent }%
+long-press = Długie przytrzymanie:{" "}
+long-press-seconds = { $seconds } s
+mute = Wycisz:{" "}
+
+action-reveal = Odkryj
+action-flag = Flaga
+action-chord = Akord
+action-pan = Przesuń
+action-zoom-in = Przybliż
+action-zoom-out = Oddal
+action-restart = Od nowa
+action-menu = Menu
+action-pause = Pauza
+action-hint = Podpowiedź
+action-tiles = Kafelki
+action-export = Eksportuj planszę
+
+action-cursor-up = Kursor w górę
+action-cursor-down = Kursor w dół
+action-cursor-left = Kursor w lewo
+action-cursor-right = Kursor w prawo
+action-cursor-reveal = Odkryj pod kursorem
+action-cursor-flag = Flaga pod kursorem
+
+mouse-left = Lewy
+mouse-right = Prawy
+mouse-middle = Środkowy
+mouse-back = Wstecz
+mouse-forward = Dalej
+mouse-other = Przycisk { $number }
+binding-click = { $button } klik
+binding-pair = { $first }+{ $second } klik
+
+announce-opened = Odkryto { $count ->
+    [one] { $count } pole
+    [few] { $count } pola
+   *[many] { $count } pól
+}
+announce-flagged = Flaga w kolumnie { $column }, wierszu { $row }
+announce-unflagged = Usunięto flagę w kolumnie { $column }, wierszu { $row }
+announce-mines = { $mines ->
+    [one] Pozostała { $mines } mina
+    [few] Pozostały { $mines } miny
+   *[many] Pozostało { $mines } min
+}
+announce-joined = { $first }, { $second }
+announce-won = Wygrana w czasie { $time }
+announce-lost = Przegrana, mina w kolumnie { $column }, wierszu { $row }
+announce-tile = Kolumna { $column }, wiersz { $row }: { $tile }
+
+tile-covered = zakryte
+tile-flagged = flaga
+tile-empty = puste
+tile-number = { $count }
+tile-mine = mina
+tile-wrong-flag = błędna flaga
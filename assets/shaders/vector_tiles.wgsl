@@ -0,0 +1,188 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+struct Palette {
+    covered: vec4<f32>,
+    uncovered: vec4<f32>,
+    grid: vec4<f32>,
+    flag: vec4<f32>,
+    mine: vec4<f32>,
+    clicked: vec4<f32>,
+    numbers: array<vec4<f32>, 8>,
+    cues: u32,
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var faces: texture_2d<u32>;
+@group(#{MATERIAL_BIND_GROUP}) @binding(1) var<uniform> palette: Palette;
+
+const COVERED: u32 = 0u;
+const PRESSED: u32 = 1u;
+const FLAGGED: u32 = 2u;
+const EMPTY: u32 = 3u;
+const BOMB: u32 = 4u;
+const BOMB_CLICKED: u32 = 5u;
+const WRONG_FLAG: u32 = 6u;
+const NEIGHBOURS: u32 = 16u;
+const NONE: u32 = 0xffffu;
+
+const SEGMENTS: array<u32, 10> = array<u32, 10>(
+    0x3fu, 0x06u, 0x5bu, 0x4fu, 0x66u, 0x6du, 0x7du, 0x07u, 0x7fu, 0x6fu,
+);
+
+fn coverage(d: f32, aa: f32) -> f32 {
+    return clamp(0.5 - d / aa, 0.0, 1.0);
+}
+
+fn box(p: vec2<f32>, half: vec2<f32>) -> f32 {
+    let q = abs(p) - half;
+    return length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0);
+}
+
+fn segment(p: vec2<f32>, a: vec2<f32>, b: vec2<f32>) -> f32 {
+    let pa = p - a;
+    let ba = b - a;
+    let h = clamp(dot(pa, ba) / dot(ba, ba), 0.0, 1.0);
+    return length(pa - ba * h);
+}
+
+fn triangle(p: vec2<f32>, a: vec2<f32>, b: vec2<f32>, c: vec2<f32>) -> f32 {
+    let inside = sign((b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x))
+        + sign((c.x - b.x) * (p.y - b.y) - (c.y - b.y) * (p.x - b.x))
+        + sign((a.x - c.x) * (p.y - c.y) - (a.y - c.y) * (p.x - c.x));
+    let d = min(min(segment(p, a, b), segment(p, b, c)), segment(p, c, a));
+    return select(d, -d, abs(inside) > 2.5);
+}
+
+fn digit(p: vec2<f32>, n: u32) -> f32 {
+    let w = 0.14;
+    let h = 0.26;
+    let tl = vec2<f32>(-w, -h);
+    let tr = vec2<f32>(w, -h);
+    let ml = vec2<f32>(-w, 0.0);
+    let mr = vec2<f32>(w, 0.0);
+    let bl = vec2<f32>(-w, h);
+    let br = vec2<f32>(w, h);
+    var ends = array<vec4<f32>, 7>(
+        vec4<f32>(tl, tr),
+        vec4<f32>(tr, mr),
+        vec4<f32>(mr, br),
+        vec4<f32>(bl, br),
+        vec4<f32>(ml, bl),
+        vec4<f32>(tl, ml),
+        vec4<f32>(ml, mr),
+    );
+    let mask = SEGMENTS[n];
+    var d = 1e3;
+    for (var i = 0u; i < 7u; i++) {
+        if ((mask >> i & 1u) != 0u) {
+            d = min(d, segment(p, ends[i].xy, ends[i].zw));
+        }
+    }
+    return d - 0.055;
+}
+
+fn number(p: vec2<f32>, n: u32) -> f32 {
+    var count = 1u;
+    for (var rest = n / 10u; rest > 0u; rest /= 10u) {
+        count++;
+    }
+    let advance = 0.42;
+    let scale = min(1.0, 0.8 / (f32(count) * advance));
+    var d = 1e3;
+    var rest = n;
+    for (var i = 0u; i < count; i++) {
+        let x = (f32(count - 1u) * 0.5 - f32(i)) * advance * scale;
+        d = min(d, digit((p - vec2<f32>(x, 0.0)) / scale, rest % 10u) * scale);
+        rest /= 10u;
+    }
+    return d;
+}
+
+fn mine(p: vec2<f32>) -> f32 {
+    var d = length(p) - 0.2;
+    d = min(d, segment(p, vec2<f32>(-0.32, 0.0), vec2<f32>(0.32, 0.0)) - 0.03);
+    d = min(d, segment(p, vec2<f32>(0.0, -0.32), vec2<f32>(0.0, 0.32)) - 0.03);
+    d = min(d, segment(p, vec2<f32>(-0.23, -0.23), vec2<f32>(0.23, 0.23)) - 0.03);
+    return min(d, segment(p, vec2<f32>(-0.23, 0.23), vec2<f32>(0.23, -0.23)) - 0.03);
+}
+
+fn covered(p: vec2<f32>, aa: f32) -> vec4<f32> {
+    let light = mix(palette.covered, vec4<f32>(1.0), 0.3);
+    let dark = mix(palette.covered, vec4<f32>(0.0, 0.0, 0.0, 1.0), 0.35);
+    let edge = mix(dark, light, coverage(p.x + p.y, aa));
+    return mix(edge, palette.covered, coverage(box(p, vec2<f32>(0.38)), aa));
+}
+
+fn stripes(p: vec2<f32>, color: vec4<f32>) -> vec4<f32> {
+    if palette.cues == 0u || fract((p.x + p.y) * 4.0) < 0.5 {
+        return color;
+    }
+    return mix(color, vec4<f32>(0.0, 0.0, 0.0, 1.0), 0.45);
+}
+
+fn flag(p: vec2<f32>, aa: f32, background: vec4<f32>) -> vec4<f32> {
+    let pole = min(
+        box(p - vec2<f32>(0.06, 0.0), vec2<f32>(0.03, 0.3)),
+        box(p - vec2<f32>(0.06, 0.27), vec2<f32>(0.2, 0.04)),
+    );
+    let flag = triangle(
+        p,
+        vec2<f32>(0.09, -0.3),
+        vec2<f32>(0.09, 0.02),
+        vec2<f32>(-0.25, -0.14),
+    );
+    let color = mix(background, palette.mine, coverage(pole, aa));
+    return mix(color, stripes(p, palette.flag), coverage(flag, aa));
+}
+
+fn uncovered(p: vec2<f32>, aa: f32, background: vec4<f32>) -> vec4<f32> {
+    return mix(palette.grid, background, coverage(box(p, vec2<f32>(0.47)), aa));
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let size = textureDimensions(faces, 0);
+    let uv = in.uv * vec2<f32>(size);
+    let aa = length(fwidth(uv));
+    let cell = clamp(vec2<u32>(floor(uv)), vec2<u32>(0u), size - 1u);
+    let face = textureLoad(faces, cell, 0).r;
+    let p = fract(uv) - 0.5;
+    if face == NONE {
+        discard;
+    }
+    switch face {
+        case COVERED: {
+            return covered(p, aa);
+        }
+        case FLAGGED: {
+            return flag(p, aa, covered(p, aa));
+        }
+        case WRONG_FLAG: {
+            let color = flag(p, aa, uncovered(p, aa, palette.clicked));
+            if palette.cues == 0u {
+                return color;
+            }
+            let cross = min(
+                segment(p, vec2<f32>(-0.3), vec2<f32>(0.3)),
+                segment(p, vec2<f32>(-0.3, 0.3), vec2<f32>(0.3, -0.3)),
+            ) - 0.05;
+            return mix(color, palette.mine, coverage(cross, aa));
+        }
+        case BOMB, BOMB_CLICKED: {
+            var background = palette.uncovered;
+            if face == BOMB_CLICKED {
+                background = stripes(p, palette.clicked);
+            }
+            let color = mix(uncovered(p, aa, background), palette.mine, coverage(mine(p), aa));
+            let shine = length(p + vec2<f32>(0.07)) - 0.05;
+            return mix(color, vec4<f32>(1.0), coverage(shine, aa));
+        }
+        case PRESSED, EMPTY: {
+            return uncovered(p, aa, palette.uncovered);
+        }
+        default: {
+            let n = face - NEIGHBOURS;
+            let color = palette.numbers[(n + 7u) % 8u];
+            return mix(uncovered(p, aa, palette.uncovered), color, coverage(number(p, n), aa));
+        }
+    }
+}
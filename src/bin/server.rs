@@ -0,0 +1,54 @@
+#![warn(clippy::pedantic)]
+
+use std::net::TcpListener;
+use std::{env, process};
+
+use minesweeper::net::{PROTOCOL_VERSION, Session, serve};
+use minesweeper::rules::{Difficulty, Size};
+
+const SIZES: [(&str, Size); 6] = [
+    ("small", Size::Small),
+    ("medium", Size::Medium),
+    ("big", Size::Big),
+    ("huge", Size::Huge),
+    ("giant", Size::Giant),
+    ("colossal", Size::Colossal),
+];
+const DIFFICULTIES: [(&str, Difficulty); 4] = [
+    ("easy", Difficulty::Easy),
+    ("medium", Difficulty::Medium),
+    ("hard", Difficulty::Hard),
+    ("extreme", Difficulty::Extreme),
+];
+const ADDRESS: &str = "127.0.0.1:7878";
+
+fn option<T: Copy>(args: &[String], name: &str, choices: &[(&str, T)], default: usize) -> T {
+    let Some(index) = args.iter().position(|arg| arg == name) else { return choices[default].1 };
+    let value = args.get(index + 1).map(String::as_str).unwrap_or_default();
+    choices.iter().find(|&&(choice, _)| choice == value).map_or_else(
+        || {
+            let names: Vec<_> = choices.iter().map(|&(choice, _)| choice).collect();
+            eprintln!("{name} must be one of: {}", names.join(", "));
+            process::exit(2);
+        },
+        |&(_, value)| value,
+    )
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let size = option(&args, "--size", &SIZES, 3);
+    let difficulty = option(&args, "--difficulty", &DIFFICULTIES, 0);
+    let address = args
+        .iter()
+        .position(|arg| arg == "--bind")
+        .and_then(|index| args.get(index + 1))
+        .map_or(ADDRESS, String::as_str);
+    let listener = TcpListener::bind(address).unwrap_or_else(|e| {
+        eprintln!("Failed to listen on {address}: {e}");
+        process::exit(1);
+    });
+    let session = Session::new(size, difficulty).expect("server boards have fixed dimensions");
+    println!("Serving a {size:?} {difficulty:?} board on {address} (protocol {PROTOCOL_VERSION})");
+    serve(&listener, session);
+}
@@ -0,0 +1,310 @@
+#![warn(clippy::pedantic)]
+#![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+
+use std::io::{self, Stdout, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use std::{env, fs, process};
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::style::{Attribute, Color, Print, SetAttribute, SetForegroundColor};
+use crossterm::{cursor, execute, queue, terminal};
+use minesweeper::rules::{Board, Coordinates, Difficulty, Face, Layout, Play, Size};
+
+const SIZES: [(&str, Size); 7] = [
+    ("small", Size::Small),
+    ("medium", Size::Medium),
+    ("big", Size::Big),
+    ("huge", Size::Huge),
+    ("giant", Size::Giant),
+    ("colossal", Size::Colossal),
+    ("endless", Size::Endless),
+];
+const DIFFICULTIES: [(&str, Difficulty); 5] = [
+    ("easy", Difficulty::Easy),
+    ("medium", Difficulty::Medium),
+    ("hard", Difficulty::Hard),
+    ("extreme", Difficulty::Extreme),
+    ("debug", Difficulty::Debug),
+];
+const NUMBERS: [Color; 8] = [
+    Color::Blue,
+    Color::Green,
+    Color::Red,
+    Color::DarkBlue,
+    Color::DarkRed,
+    Color::Cyan,
+    Color::Magenta,
+    Color::Grey,
+];
+const HELP: &str = "arrows/hjkl move  space reveal  f flag  c chord  ? hint  r restart  s size  \
+                    d difficulty  e export  q quit";
+const EXPORT_FILE: &str = "board.txt";
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Status {
+    Playing,
+    Won,
+    Lost,
+}
+
+struct Game {
+    size: usize,
+    difficulty: usize,
+    layout: Option<Layout>,
+    board: Board,
+    notice: String,
+    status: Status,
+    cursor: Coordinates,
+    offset: Coordinates,
+    started: Instant,
+    elapsed: Option<Duration>,
+}
+
+impl Game {
+    fn new(size: usize, difficulty: usize, layout: Option<Layout>) -> Self {
+        let mut board = match &layout {
+            Some(layout) => Board::from_layout(layout.clone(), DIFFICULTIES[difficulty].1),
+            None => Board::new(SIZES[size].1, DIFFICULTIES[difficulty].1),
+        };
+        board.start();
+        let cursor = board
+            .extent()
+            .filter(|_| !board.is_endless())
+            .map_or(Coordinates::default(), |(_, max)| Coordinates::new(max.x / 2, max.y / 2));
+        Self {
+            size,
+            difficulty,
+            layout,
+            board,
+            notice: String::new(),
+            status: Status::Playing,
+            cursor,
+            offset: Coordinates::default(),
+            started: Instant::now(),
+            elapsed: None,
+        }
+    }
+
+    fn elapsed(&self) -> Duration { self.elapsed.unwrap_or_else(|| self.started.elapsed()) }
+
+    fn step(&mut self, dx: i32, dy: i32) {
+        let target = Coordinates::new(self.cursor.x + dx, self.cursor.y + dy);
+        if self.board.contains(target) {
+            self.cursor = target;
+        }
+    }
+
+    fn reveal(&mut self) {
+        if self.board.left_click(self.cursor) == Some(Play::Chord) {
+            self.board.chord_click(self.cursor);
+        }
+    }
+
+    fn hint(&mut self) {
+        if let Some(coordinates) = self.board.hint() {
+            self.cursor = coordinates;
+            self.board.reveal([coordinates]);
+        }
+    }
+
+    fn update_status(&mut self) {
+        let status = if self.board.is_exploded() {
+            Status::Lost
+        } else if self.board.is_cleared() {
+            Status::Won
+        } else {
+            return;
+        };
+        self.status = status;
+        self.elapsed = Some(self.started.elapsed());
+        self.board.finish(status == Status::Won);
+    }
+
+    fn export(&mut self) {
+        let Some(layout) = self.board.export(true) else {
+            self.notice = String::from("Endless boards cannot be exported");
+            return;
+        };
+        let Some(path) = export_path() else {
+            self.notice = String::from("No configuration directory to export to");
+            return;
+        };
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(&path, layout.to_string()));
+        self.notice = match result {
+            Ok(()) => format!("Exported to {}", path.display()),
+            Err(e) => format!("Failed to export: {e}"),
+        };
+    }
+
+    fn handle(&mut self, key: KeyCode) -> bool {
+        self.notice.clear();
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('r') => *self = Self::new(self.size, self.difficulty, self.layout.take()),
+            KeyCode::Char('s') => {
+                *self = Self::new((self.size + 1) % SIZES.len(), self.difficulty, None);
+            },
+            KeyCode::Char('d') => {
+                let difficulty = (self.difficulty + 1) % DIFFICULTIES.len();
+                *self = Self::new(self.size, difficulty, self.layout.take());
+            },
+            KeyCode::Char('e') => self.export(),
+            KeyCode::Up | KeyCode::Char('k') => self.step(0, -1),
+            KeyCode::Down | KeyCode::Char('j') => self.step(0, 1),
+            KeyCode::Left | KeyCode::Char('h') => self.step(-1, 0),
+            KeyCode::Right | KeyCode::Char('l') => self.step(1, 0),
+            _ if self.status != Status::Playing => {},
+            KeyCode::Char(' ') | KeyCode::Enter => self.reveal(),
+            KeyCode::Char('f') => {
+                self.board.right_click(self.cursor, false);
+            },
+            KeyCode::Char('c') => {
+                self.board.chord_click(self.cursor);
+            },
+            KeyCode::Char('?') => self.hint(),
+            _ => {},
+        }
+        if self.status == Status::Playing {
+            self.update_status();
+        }
+        true
+    }
+
+    fn scroll(&mut self, columns: i32, rows: i32) {
+        let (cursor, offset) = (self.cursor, &mut self.offset);
+        offset.x = offset.x.clamp(cursor.x - columns + 1, cursor.x);
+        offset.y = offset.y.clamp(cursor.y - rows + 1, cursor.y);
+    }
+
+    fn status_line(&self) -> String {
+        let seconds = self.elapsed().as_secs();
+        let time = match (seconds / 3600, seconds / 60 % 60, seconds % 60) {
+            (0, 0, s) => format!("{s}"),
+            (0, m, s) => format!("{m}:{s:02}"),
+            (h, m, s) => format!("{h}:{m:02}:{s:02}"),
+        };
+        let mines = if self.board.is_endless() {
+            format!("Revealed: {}", self.board.uncovered())
+        } else {
+            format!("Mines: {}", self.board.remaining_mines())
+        };
+        let status = match self.status {
+            Status::Playing => "",
+            Status::Won => "  You won!",
+            Status::Lost => "  Game over",
+        };
+        let size = if self.layout.is_some() { "custom" } else { SIZES[self.size].0 };
+        let difficulty = DIFFICULTIES[self.difficulty].0;
+        format!("{size} {difficulty}  {mines}  Time: {time}{status}  {}", self.notice)
+    }
+
+    fn draw(&mut self, out: &mut Stdout) -> io::Result<()> {
+        let (width, height) = terminal::size()?;
+        let (columns, rows) = (i32::from(width) / 2, i32::from(height).saturating_sub(2).max(1));
+        self.scroll(columns, rows);
+        let clear = terminal::Clear(terminal::ClearType::UntilNewLine);
+        queue!(out, cursor::MoveTo(0, 0), Print(self.status_line()), clear)?;
+        for row in 0..rows {
+            queue!(out, cursor::MoveTo(0, row as u16 + 1))?;
+            for column in 0..columns {
+                let coordinates = Coordinates::new(self.offset.x + column, self.offset.y + row);
+                if !self.board.contains(coordinates) {
+                    queue!(out, Print("  "))?;
+                    continue;
+                }
+                let (symbol, color) = glyph(self.board.face(coordinates));
+                let highlight =
+                    if coordinates == self.cursor { Attribute::Reverse } else { Attribute::Reset };
+                queue!(
+                    out,
+                    SetAttribute(highlight),
+                    SetForegroundColor(color),
+                    Print(symbol),
+                    Print(' '),
+                    SetAttribute(Attribute::Reset),
+                )?;
+            }
+            queue!(out, clear)?;
+        }
+        queue!(out, cursor::MoveTo(0, height.saturating_sub(1)), Print(HELP), clear)?;
+        out.flush()
+    }
+}
+
+struct Terminal(Stdout);
+
+impl Terminal {
+    fn enter() -> io::Result<Self> {
+        let mut out = io::stdout();
+        terminal::enable_raw_mode()?;
+        execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Self(out))
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        execute!(self.0, cursor::Show, terminal::LeaveAlternateScreen).ok();
+        terminal::disable_raw_mode().ok();
+    }
+}
+
+fn export_path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("minesweeper").join(EXPORT_FILE))
+}
+
+fn glyph(face: Face) -> (char, Color) {
+    match face {
+        Face::Covered => ('.', Color::DarkGrey),
+        Face::Pressed => (',', Color::Grey),
+        Face::Flagged => ('F', Color::Red),
+        Face::Empty => (' ', Color::Reset),
+        Face::Neighbours(n) => (char::from(b'0' + n), NUMBERS[usize::from(n - 1)]),
+        Face::Bomb => ('*', Color::White),
+        Face::BombClicked => ('*', Color::Red),
+        Face::WrongFlag => ('X', Color::Yellow),
+    }
+}
+
+fn option(args: &[String], name: &str, choices: &[&str]) -> usize {
+    let Some(index) = args.iter().position(|arg| arg == name) else { return 0 };
+    let value = args.get(index + 1).map(String::as_str).unwrap_or_default();
+    choices.iter().position(|&choice| choice == value).unwrap_or_else(|| {
+        eprintln!("{name} must be one of: {}", choices.join(", "));
+        process::exit(2);
+    })
+}
+
+fn run(out: &mut Stdout, mut game: Game) -> io::Result<()> {
+    loop {
+        game.draw(out)?;
+        if !event::poll(Duration::from_millis(250))? {
+            continue;
+        }
+        if let Event::Key(KeyEvent { code, kind: KeyEventKind::Press, .. }) = event::read()?
+            && !game.handle(code)
+        {
+            return Ok(());
+        }
+    }
+}
+
+fn main() -> io::Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let size = option(&args, "--size", &SIZES.map(|(name, _)| name));
+    let difficulty = option(&args, "--difficulty", &DIFFICULTIES.map(|(name, _)| name));
+    let layout = args.iter().position(|arg| arg == "--board").map(|index| {
+        let path = args.get(index + 1).map(String::as_str).unwrap_or_default();
+        let text = fs::read_to_string(path).map_err(|e| e.to_string());
+        text.and_then(|text| Layout::parse(&text)).unwrap_or_else(|e| {
+            eprintln!("Failed to import {path}: {e}");
+            process::exit(2);
+        })
+    });
+    let mut terminal = Terminal::enter()?;
+    run(&mut terminal.0, Game::new(size, difficulty, layout))
+}
@@ -0,0 +1,48 @@
+#![warn(clippy::pedantic)]
+
+use std::{env, fs, process};
+
+use minesweeper::video::Video;
+
+fn main() {
+    let paths: Vec<String> = env::args().skip(1).collect();
+    if paths.is_empty() {
+        eprintln!("usage: verify <replay.rawvf>...");
+        process::exit(2);
+    }
+    let mut failed = false;
+    for path in paths {
+        let text = fs::read_to_string(&path).map_err(|e| e.to_string());
+        let video = match text.and_then(|text| Video::parse(&text)) {
+            Ok(video) => video,
+            Err(e) => {
+                eprintln!("{path}: failed to read: {e}");
+                failed = true;
+                continue;
+            },
+        };
+        match video.verify() {
+            Ok(outcome) => {
+                let clicks = outcome.clicks;
+                println!(
+                    "{path}: ok, {} in {:.3}s, 3BV {}, clicks {}/{}/{}",
+                    if video.won { "won" } else { "lost" },
+                    outcome.time,
+                    outcome.bbbv,
+                    clicks.left,
+                    clicks.right,
+                    clicks.chords
+                );
+            },
+            Err(errors) => {
+                for error in errors {
+                    eprintln!("{path}: {error}");
+                }
+                failed = true;
+            },
+        }
+    }
+    if failed {
+        process::exit(1);
+    }
+}
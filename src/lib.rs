@@ -0,0 +1,36 @@
+#![warn(clippy::pedantic)]
+#![allow(
+    clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_possible_wrap,
+    clippy::cast_sign_loss, clippy::needless_pass_by_value, clippy::type_complexity
+)]
+
+#[cfg(feature = "bevy")]
+use bevy::prelude::*;
+
+pub mod net;
+#[cfg(feature = "bevy")]
+pub mod plugins;
+pub mod rules;
+#[cfg(feature = "bevy")]
+mod utils;
+pub mod video;
+
+#[cfg(feature = "bevy")]
+#[derive(States, Copy, Clone, PartialEq, Eq, Debug, Hash, Default)]
+pub enum AppState {
+    #[default]
+    Menu,
+    Playing,
+    Won,
+    Lost,
+    Settings,
+}
+
+#[cfg(feature = "bevy")]
+#[derive(SubStates, Copy, Clone, PartialEq, Eq, Debug, Hash, Default)]
+#[source(AppState = AppState::Playing)]
+pub enum Pause {
+    #[default]
+    Running,
+    Paused,
+}
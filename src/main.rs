@@ -1,47 +1,52 @@
 #![warn(clippy::pedantic)]
-#![allow(
-    clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_possible_wrap,
-    clippy::cast_sign_loss, clippy::needless_pass_by_value, clippy::type_complexity
-)]
 
 use bevy::prelude::*;
-use bevy::window::PresentMode;
-use plugins::{
-    board, camera, game_assets, hide_children_on_hover, hud, main_menu, mouse, text_val_size,
+use minesweeper::plugins::{
+    accessibility, actions, animations, audio, board, camera, coop, daily, flags, game_assets,
+    hide_children_on_hover, hud, keyboard, layout, localization, main_menu, mouse, race, replay,
+    settings, text_val_size, theme, tilemap, touch,
 };
-
-mod plugins;
-mod utils;
-
-#[derive(States, Copy, Clone, PartialEq, Eq, Debug, Hash, Default)]
-enum AppState {
-    #[default]
-    Menu,
-    Playing,
-    Won,
-    Lost,
-}
+use minesweeper::{AppState, Pause};
 
 fn main() {
     App::new()
+        .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
+        // .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()).set(WindowPlugin {
+        //     primary_window: Some(Window {
+        //         present_mode: bevy::window::PresentMode::AutoNoVsync,
+        //         ..default()
+        //     }),
+        //     ..default()
+        // }))
         .add_plugins((
-            DefaultPlugins.set(ImagePlugin::default_nearest()),
-            // DefaultPlugins.set(ImagePlugin::default_nearest()).set(WindowPlugin {
-            //     primary_window: Some(Window {
-            //         present_mode: PresentMode::AutoNoVsync,
-            //         ..default()
-            //     }),
-            //     ..default()
-            // }),
+            accessibility,
+            actions,
+            animations,
+            audio,
             board,
             camera,
+            coop,
+            daily,
+            flags,
             game_assets,
             hide_children_on_hover,
             hud,
+        ))
+        .add_plugins((
+            keyboard,
+            layout,
+            localization,
             main_menu,
             mouse,
+            race,
+            replay,
+            settings,
             text_val_size,
+            theme,
+            tilemap,
+            touch,
         ))
         .init_state::<AppState>()
+        .add_sub_state::<Pause>()
         .run();
 }
@@ -0,0 +1,344 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use itertools::iproduct;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::rules::{Board, Coordinates, Difficulty, Face, Size};
+
+pub const PROTOCOL_VERSION: u32 = 1;
+const LINE_LIMIT: u64 = 256;
+const READ_TIMEOUT: Duration = Duration::from_mins(5);
+const WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+const QUEUE_LIMIT: usize = 256;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum ClientMessage {
+    Hello { version: u32 },
+    Cursor { x: f32, y: f32 },
+    Reveal(Coordinates),
+    Flag(Coordinates),
+    Chord(Coordinates),
+    Restart,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum ServerMessage {
+    Welcome { version: u32, player: u32 },
+    Rejected(String),
+    Board { size: Size, difficulty: Difficulty, faces: String, owners: Vec<(Coordinates, u32)> },
+    Tiles { player: u32, tiles: Vec<(Coordinates, Face)> },
+    Flagged { player: u32, coordinates: Coordinates, placed: bool },
+    Cursor { player: u32, x: f32, y: f32 },
+    Left { player: u32 },
+}
+
+#[allow(clippy::missing_errors_doc)]
+pub fn write_message(writer: &mut impl Write, message: &impl Serialize) -> io::Result<()> {
+    let mut line = ron::to_string(message).map_err(io::Error::other)?;
+    line.push('\n');
+    writer.write_all(line.as_bytes())
+}
+
+#[allow(clippy::missing_errors_doc)]
+pub fn read_message<T: DeserializeOwned>(line: &str) -> Result<T, String> {
+    ron::from_str(line.trim()).map_err(|e| e.to_string())
+}
+
+pub fn grid(size: Size) -> impl Iterator<Item = Coordinates> {
+    let (width, height) = size.dimensions().unwrap_or_default();
+    iproduct!(0..height, 0..width).map(|(y, x)| Coordinates::new(x as i32, y as i32))
+}
+
+fn encode(face: Face) -> char { char::from(b'a' + face.index() as u8) }
+
+#[must_use]
+pub fn decode(face: char) -> Option<Face> {
+    let index = u32::from(face).checked_sub(u32::from('a'))?;
+    Face::ALL.get(index as usize).copied()
+}
+
+fn accept(message: &ClientMessage) -> Result<(), String> {
+    match message {
+        ClientMessage::Hello { version } if *version == PROTOCOL_VERSION => Ok(()),
+        ClientMessage::Hello { version } => {
+            Err(format!("protocol version {version} is not supported, expected {PROTOCOL_VERSION}"))
+        },
+        _ => Err("expected a hello message".to_string()),
+    }
+}
+
+pub struct Session {
+    board: Board,
+    faces: Vec<Face>,
+    owners: HashMap<Coordinates, u32>,
+    players: u32,
+}
+
+impl Session {
+    #[must_use]
+    pub fn new(size: Size, difficulty: Difficulty) -> Option<Self> {
+        size.dimensions()?;
+        let board = Board::new(size, difficulty);
+        let mut session = Self { board, faces: Vec::new(), owners: HashMap::new(), players: 0 };
+        session.restart();
+        Some(session)
+    }
+
+    fn restart(&mut self) {
+        self.board = Board::new(self.board.size, self.board.difficulty);
+        self.board.start();
+        self.faces = grid(self.board.size).map(|c| self.board.face(c)).collect();
+        self.owners.clear();
+    }
+
+    fn is_over(&self) -> bool { self.board.is_exploded() || self.board.is_cleared() }
+
+    pub fn join(&mut self) -> (u32, Vec<ServerMessage>) {
+        let player = self.players;
+        self.players += 1;
+        (player, vec![
+            ServerMessage::Welcome { version: PROTOCOL_VERSION, player },
+            self.snapshot(),
+        ])
+    }
+
+    pub fn snapshot(&self) -> ServerMessage {
+        ServerMessage::Board {
+            size: self.board.size,
+            difficulty: self.board.difficulty,
+            faces: self.faces.iter().copied().map(encode).collect(),
+            owners: self.owners.iter().map(|(&c, &player)| (c, player)).collect(),
+        }
+    }
+
+    pub fn handle(&mut self, player: u32, message: ClientMessage) -> Vec<ServerMessage> {
+        let coordinates = match message {
+            ClientMessage::Cursor { x, y } => return vec![ServerMessage::Cursor { player, x, y }],
+            ClientMessage::Restart if self.is_over() => {
+                self.restart();
+                return vec![self.snapshot()];
+            },
+            ClientMessage::Reveal(c) | ClientMessage::Flag(c) | ClientMessage::Chord(c)
+                if self.board.contains(c) && !self.is_over() =>
+            {
+                c
+            },
+            _ => return Vec::new(),
+        };
+        match (message, self.board.face(coordinates)) {
+            (ClientMessage::Flag(_), Face::Covered | Face::Flagged) => {
+                self.board.toggle_flag(coordinates);
+                let placed = self.board.face(coordinates) == Face::Flagged;
+                if placed {
+                    self.owners.insert(coordinates, player);
+                } else {
+                    self.owners.remove(&coordinates);
+                }
+                self.diff(coordinates);
+                return vec![ServerMessage::Flagged { player, coordinates, placed }];
+            },
+            (ClientMessage::Reveal(_), Face::Covered) => {
+                self.board.reveal([coordinates]);
+            },
+            (ClientMessage::Reveal(_) | ClientMessage::Chord(_), Face::Neighbours(_)) => {
+                self.board.chord(coordinates);
+            },
+            _ => return Vec::new(),
+        }
+        if self.board.is_exploded() {
+            self.board.finish(false);
+        } else if self.board.is_cleared() {
+            self.board.finish(true);
+        }
+        let tiles = self.diff(coordinates);
+        if tiles.is_empty() { Vec::new() } else { vec![ServerMessage::Tiles { player, tiles }] }
+    }
+
+    fn diff(&mut self, origin: Coordinates) -> Vec<(Coordinates, Face)> {
+        let Self { board, faces, .. } = self;
+        let mut tiles: Vec<_> = grid(board.size)
+            .zip(faces.iter_mut())
+            .filter_map(|(c, face)| {
+                let current = board.face(c);
+                (current != *face).then(|| {
+                    *face = current;
+                    (c, current)
+                })
+            })
+            .collect();
+        tiles.sort_by_key(|&(c, _)| c.distance(origin));
+        tiles
+    }
+}
+
+struct Shared {
+    session: Session,
+    clients: HashMap<u32, SyncSender<ServerMessage>>,
+}
+
+impl Shared {
+    fn broadcast(&mut self, messages: &[ServerMessage]) {
+        self.clients.retain(|_, sender| {
+            messages.iter().all(|message| sender.try_send(message.clone()).is_ok())
+        });
+    }
+}
+
+fn send_all(mut stream: TcpStream, messages: Receiver<ServerMessage>) {
+    for message in messages {
+        if write_message(&mut stream, &message).is_err() {
+            break;
+        }
+    }
+    stream.shutdown(Shutdown::Both).ok();
+}
+
+pub fn serve(listener: &TcpListener, session: Session) {
+    let shared = Arc::new(Mutex::new(Shared { session, clients: HashMap::new() }));
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        let shared = shared.clone();
+        thread::spawn(move || {
+            let peer = stream.peer_addr().map(|a| a.to_string()).unwrap_or_default();
+            if let Err(e) = connect(stream, &shared) {
+                eprintln!("{peer}: {e}");
+            }
+        });
+    }
+}
+
+fn read_line(reader: &mut impl BufRead) -> Option<String> {
+    let mut line = String::new();
+    reader.take(LINE_LIMIT).read_line(&mut line).ok()?;
+    line.ends_with('\n').then_some(line)
+}
+
+fn connect(mut stream: TcpStream, shared: &Mutex<Shared>) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let hello = read_line(&mut reader).unwrap_or_default();
+    if let Err(reason) = read_message(&hello).and_then(|hello| accept(&hello)) {
+        return write_message(&mut stream, &ServerMessage::Rejected(reason));
+    }
+    let (sender, receiver) = mpsc::sync_channel(QUEUE_LIMIT);
+    thread::spawn(move || send_all(stream, receiver));
+    let player = {
+        let mut shared = shared.lock().unwrap_or_else(PoisonError::into_inner);
+        let (player, messages) = shared.session.join();
+        for message in messages {
+            sender.try_send(message).ok();
+        }
+        shared.clients.insert(player, sender);
+        player
+    };
+    while let Some(line) = read_line(&mut reader) {
+        let Ok(message) = read_message(&line) else { continue };
+        let mut shared = shared.lock().unwrap_or_else(PoisonError::into_inner);
+        let messages = shared.session.handle(player, message);
+        shared.broadcast(&messages);
+    }
+    let mut shared = shared.lock().unwrap_or_else(PoisonError::into_inner);
+    shared.clients.remove(&player);
+    shared.broadcast(&[ServerMessage::Left { player }]);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oversized_lines_are_rejected() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let session = Session::new(Size::Small, Difficulty::Easy).unwrap();
+        thread::spawn(move || serve(&listener, session));
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.write_all(&[b'x'; 4096]).unwrap();
+        let mut reply = String::new();
+        BufReader::new(stream).read_line(&mut reply).unwrap();
+        assert!(matches!(read_message(&reply), Ok(ServerMessage::Rejected(_))));
+        let mut stream = TcpStream::connect(address).unwrap();
+        write_message(&mut stream, &ClientMessage::Hello { version: PROTOCOL_VERSION }).unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        for _ in 0..2 {
+            reader.read_line(&mut String::new()).unwrap();
+        }
+        stream.write_all(&[b'x'; 4096]).unwrap();
+        assert!(!matches!(reader.read_line(&mut String::new()), Ok(read) if read > 0));
+    }
+
+    #[test]
+    fn sessions_share_reveals_and_flag_owners() {
+        let mut session = Session::new(Size::Small, Difficulty::Easy).unwrap();
+        assert_eq!(accept(&ClientMessage::Hello { version: PROTOCOL_VERSION }), Ok(()));
+        assert!(accept(&ClientMessage::Hello { version: PROTOCOL_VERSION + 1 }).is_err());
+        assert!(accept(&ClientMessage::Restart).is_err());
+        let (first, _) = session.join();
+        let (second, messages) = session.join();
+        assert_eq!((first, second), (0, 1));
+        let ServerMessage::Board { faces, .. } = &messages[1] else { panic!() };
+        assert_eq!(faces.len(), 16 * 9);
+        assert!(faces.chars().all(|face| decode(face) == Some(Face::Covered)));
+        let flag = Coordinates::new(3, 4);
+        let placed = session.handle(second, ClientMessage::Flag(flag));
+        assert_eq!(placed, [ServerMessage::Flagged {
+            player: second,
+            coordinates: flag,
+            placed: true
+        }]);
+        let ServerMessage::Board { owners, .. } = session.snapshot() else { panic!() };
+        assert_eq!(owners, [(flag, second)]);
+        assert!(session.handle(first, ClientMessage::Reveal(flag)).is_empty());
+        let safe = session.board.hint().unwrap();
+        let ServerMessage::Tiles { player, tiles } =
+            &session.handle(first, ClientMessage::Reveal(safe))[0]
+        else {
+            panic!()
+        };
+        assert_eq!((*player, tiles[0].0), (first, safe));
+    }
+
+    #[test]
+    fn clients_play_together_over_localhost() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let session = Session::new(Size::Small, Difficulty::Easy).unwrap();
+        thread::spawn(move || serve(&listener, session));
+        let connect = || {
+            let mut stream = TcpStream::connect(&address).unwrap();
+            write_message(&mut stream, &ClientMessage::Hello { version: PROTOCOL_VERSION })
+                .unwrap();
+            let reader = BufReader::new(stream.try_clone().unwrap());
+            (stream, reader.lines())
+        };
+        let next = |lines: &mut io::Lines<BufReader<TcpStream>>| {
+            read_message::<ServerMessage>(&lines.next().unwrap().unwrap()).unwrap()
+        };
+        let (mut first, mut first_lines) = connect();
+        assert_eq!(next(&mut first_lines), ServerMessage::Welcome {
+            version: PROTOCOL_VERSION,
+            player: 0
+        });
+        assert!(matches!(next(&mut first_lines), ServerMessage::Board { size: Size::Small, .. }));
+        let (_second, mut second_lines) = connect();
+        assert!(matches!(next(&mut second_lines), ServerMessage::Welcome { player: 1, .. }));
+        assert!(matches!(next(&mut second_lines), ServerMessage::Board { .. }));
+        let coordinates = Coordinates::new(2, 2);
+        write_message(&mut first, &ClientMessage::Flag(coordinates)).unwrap();
+        let flagged = ServerMessage::Flagged { player: 0, coordinates, placed: true };
+        assert_eq!(next(&mut first_lines), flagged);
+        assert_eq!(next(&mut second_lines), flagged);
+        write_message(&mut first, &ClientMessage::Cursor { x: 1.5, y: -2.5 }).unwrap();
+        let cursor = ServerMessage::Cursor { player: 0, x: 1.5, y: -2.5 };
+        assert_eq!(next(&mut second_lines), cursor);
+    }
+}
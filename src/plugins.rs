@@ -1,17 +1,53 @@
+mod accessibility;
+mod actions;
+mod animations;
+mod audio;
 mod board;
 mod camera;
+mod coop;
+mod daily;
+mod flags;
 mod game_assets;
 mod hide_children_on_hover;
 mod hud;
+mod keyboard;
+mod layout;
+mod localization;
 mod main_menu;
 mod mouse;
+mod race;
+mod replay;
+mod settings;
 mod text_val_size;
+mod theme;
+mod tilemap;
+mod touch;
 
-pub use board::{Board, board};
-pub use camera::{CameraLimits, MainCamera, camera};
-pub use game_assets::{GameAssets, game_assets};
+pub use accessibility::{Announcement, accessibility, describe};
+pub use actions::{Action, ActionEvent, ActionEvents, Bindings, Source, actions};
+pub use animations::{Animations, animations};
+pub use audio::{GameSounds, SoundPacks, audio};
+pub use board::{FlagPlaced, FlagRemoved, RegionRevealed, board};
+pub use camera::{CameraLimits, MainCamera, Views, camera};
+pub use coop::{Owners, PLAYER_COLORS, Remote, coop};
+pub use daily::{Daily, DailyButton, DailyHistory, DailyResult, Day, daily, unranked};
+pub use flags::{Turns, flags};
+pub use game_assets::{GameAssets, TexturePacks, game_assets};
 pub use hide_children_on_hover::{HideChildrenOnHover, hide_children_on_hover};
-pub use hud::{MineCount, hud};
-pub use main_menu::{Difficulty, Size, main_menu};
-pub use mouse::{LeftClicked, RightClicked, mouse};
+pub use hud::{Elapsed, hud};
+pub use keyboard::keyboard;
+pub use layout::layout;
+pub use localization::{Languages, Locale, Localized, localization};
+pub use main_menu::main_menu;
+pub use mouse::{Chorded, LeftClicked, PressPreview, RightClicked, mouse};
+pub use race::{Controller, Mode, Player, race, spawn_players};
+pub use replay::{Replay, replay};
+pub use settings::{Preferences, settings};
 pub use text_val_size::{TextValSize, text_val_size};
+pub use theme::{
+    Colorblind, Theme, ThemeColor, ThemedBackground, ThemedBorder, ThemedText, Themes, theme,
+};
+pub use tilemap::tilemap;
+pub use touch::touch;
+
+pub use crate::rules::{Board, Clicks, Coordinates, Difficulty, Face, Layout, Size, TileState};
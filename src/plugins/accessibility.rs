@@ -0,0 +1,134 @@
+use accesskit::{Live, Node as AccessNode, Role};
+use bevy::a11y::AccessibilityNode;
+use bevy::prelude::*;
+use bevy::ui::UiSystems;
+use bevy::ui::widget::TextUiReader;
+use fluent_bundle::FluentValue;
+
+use crate::AppState;
+use crate::plugins::main_menu::MenuButton;
+use crate::plugins::{
+    Board, Coordinates, Elapsed, Face, FlagPlaced, FlagRemoved, Locale, RegionRevealed,
+};
+
+#[derive(Event)]
+pub struct Announcement(pub String);
+
+#[derive(Component)]
+struct LiveRegion;
+
+#[must_use]
+pub fn describe(board: &Board, coordinates: Coordinates, locale: &Locale) -> String {
+    let tile = match board.face(coordinates) {
+        Face::Covered | Face::Pressed => locale.text("tile-covered"),
+        Face::Flagged => locale.text("tile-flagged"),
+        Face::Empty => locale.text("tile-empty"),
+        Face::Neighbours(n) => locale.format("tile-number", [("count", n.into())]),
+        Face::Bomb | Face::BombClicked => locale.text("tile-mine"),
+        Face::WrongFlag => locale.text("tile-wrong-flag"),
+    };
+    locale.format("announce-tile", position(coordinates).chain([("tile", tile.into())]))
+}
+
+fn position(
+    coordinates: Coordinates,
+) -> impl Iterator<Item = (&'static str, FluentValue<'static>)> {
+    [("column", (coordinates.x + 1).into()), ("row", (coordinates.y + 1).into())].into_iter()
+}
+
+fn with_mines(message: String, board: &Board, locale: &Locale) -> String {
+    if board.is_endless() {
+        return message;
+    }
+    let mines = locale.format("announce-mines", [("mines", board.remaining_mines().into())]);
+    locale.format("announce-joined", [("first", message.into()), ("second", mines.into())])
+}
+
+fn spawn(mut commands: Commands) {
+    let mut node = AccessNode::new(Role::Status);
+    node.set_live(Live::Polite);
+    commands.spawn((AccessibilityNode(node), LiveRegion));
+}
+
+fn announce(
+    announcement: On<Announcement>, mut region: Single<&mut AccessibilityNode, With<LiveRegion>>,
+) {
+    region.set_label(announcement.0.as_str());
+}
+
+fn label_buttons(
+    buttons: Query<(&mut AccessibilityNode, &Children), With<MenuButton>>, mut reader: TextUiReader,
+) {
+    for (mut node, children) in buttons {
+        let mut text = String::new();
+        for &child in children {
+            for (_, _, span, _, _) in reader.iter(child) {
+                text.push(' ');
+                text.push_str(span);
+            }
+        }
+        let label = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if node.role() != Role::Button || node.label() != Some(label.as_str()) {
+            node.set_role(Role::Button);
+            node.set_label(label);
+        }
+    }
+}
+
+fn revealed(
+    revealed: On<RegionRevealed>, mut commands: Commands, boards: Query<&Board>,
+    locale: Res<Locale>,
+) {
+    let Ok(board) = boards.get(revealed.board) else { return };
+    let Some(&first) = revealed.tiles.first() else { return };
+    if revealed.tiles.iter().any(|&c| board.face(c) == Face::BombClicked) {
+        return;
+    }
+    let mut message = describe(board, first, &locale);
+    if revealed.tiles.len() > 1 {
+        let opened = locale.format("announce-opened", [("count", revealed.tiles.len().into())]);
+        message = locale
+            .format("announce-joined", [("first", message.into()), ("second", opened.into())]);
+    }
+    commands.trigger(Announcement(message));
+}
+
+fn flag_placed(
+    placed: On<FlagPlaced>, mut commands: Commands, boards: Query<&Board>, locale: Res<Locale>,
+) {
+    let Ok(board) = boards.get(placed.board) else { return };
+    let message = locale.format("announce-flagged", position(placed.coordinates));
+    commands.trigger(Announcement(with_mines(message, board, &locale)));
+}
+
+fn flag_removed(
+    removed: On<FlagRemoved>, mut commands: Commands, boards: Query<&Board>, locale: Res<Locale>,
+) {
+    let Ok(board) = boards.get(removed.board) else { return };
+    let message = locale.format("announce-unflagged", position(removed.coordinates));
+    commands.trigger(Announcement(with_mines(message, board, &locale)));
+}
+
+fn won(mut commands: Commands, elapsed: Res<Elapsed>, locale: Res<Locale>) {
+    let time = locale.duration(elapsed.0 as u32);
+    commands.trigger(Announcement(locale.format("announce-won", [("time", time.into())])));
+}
+
+fn lost(mut commands: Commands, boards: Query<&Board>, locale: Res<Locale>) {
+    let mine = boards
+        .iter()
+        .find_map(|board| board.coordinates().find(|&c| board.face(c) == Face::BombClicked));
+    let message = locale.format("announce-lost", position(mine.unwrap_or_default()));
+    commands.trigger(Announcement(message));
+}
+
+pub fn accessibility(app: &mut App) {
+    app.add_systems(Startup, spawn)
+        .add_observer(announce)
+        .add_observer(revealed)
+        .add_observer(flag_placed)
+        .add_observer(flag_removed)
+        .add_systems(OnEnter(AppState::Won), won)
+        .add_systems(OnEnter(AppState::Lost), lost)
+        .add_systems(PostUpdate, label_buttons.after(UiSystems::Prepare));
+}
@@ -0,0 +1,325 @@
+use std::collections::{BTreeMap, HashSet};
+
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use bevy::window::WindowEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::plugins::Locale;
+use crate::utils::Persistent;
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum Action {
+    Reveal,
+    Flag,
+    Chord,
+    Pan,
+    ZoomIn,
+    ZoomOut,
+    Restart,
+    Menu,
+    Pause,
+    Hint,
+    Tiles,
+    CursorUp,
+    CursorDown,
+    CursorLeft,
+    CursorRight,
+    CursorReveal,
+    CursorFlag,
+    Export,
+}
+
+impl Action {
+    pub const ALL: [Self; 18] = [
+        Self::Reveal,
+        Self::Flag,
+        Self::Chord,
+        Self::Pan,
+        Self::ZoomIn,
+        Self::ZoomOut,
+        Self::Restart,
+        Self::Menu,
+        Self::Pause,
+        Self::Hint,
+        Self::Tiles,
+        Self::CursorUp,
+        Self::CursorDown,
+        Self::CursorLeft,
+        Self::CursorRight,
+        Self::CursorReveal,
+        Self::CursorFlag,
+        Self::Export,
+    ];
+
+    #[must_use]
+    pub fn message(self) -> &'static str {
+        match self {
+            Action::Reveal => "action-reveal",
+            Action::Flag => "action-flag",
+            Action::Chord => "action-chord",
+            Action::Pan => "action-pan",
+            Action::ZoomIn => "action-zoom-in",
+            Action::ZoomOut => "action-zoom-out",
+            Action::Restart => "action-restart",
+            Action::Menu => "action-menu",
+            Action::Pause => "action-pause",
+            Action::Hint => "action-hint",
+            Action::Tiles => "action-tiles",
+            Action::CursorUp => "action-cursor-up",
+            Action::CursorDown => "action-cursor-down",
+            Action::CursorLeft => "action-cursor-left",
+            Action::CursorRight => "action-cursor-right",
+            Action::CursorReveal => "action-cursor-reveal",
+            Action::CursorFlag => "action-cursor-flag",
+            Action::Export => "action-export",
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    MousePair(MouseButton, MouseButton),
+}
+
+impl Binding {
+    fn pressed(self, held: &Held, left_handed: bool) -> bool {
+        let button = |b| held.mouse.contains(&swap_hands(b, left_handed));
+        match self {
+            Binding::Key(key) => held.keys.contains(&key),
+            Binding::Mouse(b) => button(b),
+            Binding::MousePair(a, b) => button(a) && button(b),
+        }
+    }
+
+    #[must_use]
+    pub fn uses(self, button: MouseButton) -> bool {
+        match self {
+            Binding::Key(_) => false,
+            Binding::Mouse(b) => b == button,
+            Binding::MousePair(a, b) => a == button || b == button,
+        }
+    }
+
+    pub fn display(self, left_handed: bool, locale: &Locale) -> String {
+        let button = |b| match swap_hands(b, left_handed) {
+            MouseButton::Left => locale.text("mouse-left"),
+            MouseButton::Right => locale.text("mouse-right"),
+            MouseButton::Middle => locale.text("mouse-middle"),
+            MouseButton::Back => locale.text("mouse-back"),
+            MouseButton::Forward => locale.text("mouse-forward"),
+            MouseButton::Other(n) => locale.format("mouse-other", [("number", n.into())]),
+        };
+        match self {
+            Binding::Key(key) => {
+                let name = format!("{key:?}");
+                let trimmed = name.strip_prefix("Key").or_else(|| name.strip_prefix("Digit"));
+                trimmed.map_or_else(|| name.clone(), String::from)
+            },
+            Binding::Mouse(b) => locale.format("binding-click", [("button", button(b).into())]),
+            Binding::MousePair(a, b) => locale.format("binding-pair", [
+                ("first", button(a).into()),
+                ("second", button(b).into()),
+            ]),
+        }
+    }
+}
+
+pub fn swap_hands(button: MouseButton, left_handed: bool) -> MouseButton {
+    match (button, left_handed) {
+        (MouseButton::Left, true) => MouseButton::Right,
+        (MouseButton::Right, true) => MouseButton::Left,
+        (button, _) => button,
+    }
+}
+
+#[derive(Resource, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bindings {
+    pub actions: BTreeMap<Action, Binding>,
+    pub left_handed: bool,
+    pub flag_chord: bool,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        let actions = BTreeMap::from([
+            (Action::Reveal, Binding::Mouse(MouseButton::Left)),
+            (Action::Flag, Binding::Mouse(MouseButton::Right)),
+            (Action::Chord, Binding::MousePair(MouseButton::Left, MouseButton::Right)),
+            (Action::Pan, Binding::Mouse(MouseButton::Left)),
+            (Action::ZoomIn, Binding::Key(KeyCode::Equal)),
+            (Action::ZoomOut, Binding::Key(KeyCode::Minus)),
+            (Action::Restart, Binding::Key(KeyCode::KeyR)),
+            (Action::Menu, Binding::Key(KeyCode::KeyM)),
+            (Action::Pause, Binding::Key(KeyCode::KeyP)),
+            (Action::Hint, Binding::Key(KeyCode::KeyH)),
+            (Action::Tiles, Binding::Key(KeyCode::KeyT)),
+            (Action::CursorUp, Binding::Key(KeyCode::ArrowUp)),
+            (Action::CursorDown, Binding::Key(KeyCode::ArrowDown)),
+            (Action::CursorLeft, Binding::Key(KeyCode::ArrowLeft)),
+            (Action::CursorRight, Binding::Key(KeyCode::ArrowRight)),
+            (Action::CursorReveal, Binding::Key(KeyCode::Space)),
+            (Action::CursorFlag, Binding::Key(KeyCode::KeyF)),
+            (Action::Export, Binding::Key(KeyCode::KeyE)),
+        ]);
+        Self { actions, left_handed: false, flag_chord: false }
+    }
+}
+
+impl Persistent for Bindings {
+    const FILE: &'static str = "bindings.ron";
+}
+
+impl Bindings {
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        let previous = self.actions.insert(action, binding);
+        if action == Action::Pan {
+            return;
+        }
+        let conflicts: Vec<Action> = self
+            .actions
+            .iter()
+            .filter(|&(&other, &b)| other != action && other != Action::Pan && b == binding)
+            .map(|(&other, _)| other)
+            .collect();
+        for other in conflicts {
+            match previous {
+                Some(previous) => self.actions.insert(other, previous),
+                None => self.actions.remove(&other),
+            };
+        }
+    }
+
+    #[must_use]
+    pub fn display(&self, action: Action, locale: &Locale) -> String {
+        let binding = self.actions.get(&action);
+        binding.map_or_else(|| String::from("-"), |b| b.display(self.left_handed, locale))
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Source {
+    Mouse,
+    Keyboard,
+    Touch,
+    Replay,
+    Rules,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct ActionEvent {
+    pub action: Action,
+    pub pressed: bool,
+    pub cursor: Option<Vec2>,
+    pub source: Source,
+}
+
+#[derive(Resource, Default)]
+pub struct ActionEvents(Vec<ActionEvent>);
+
+impl ActionEvents {
+    pub fn iter(&self) -> impl Iterator<Item = &ActionEvent> { self.0.iter() }
+}
+
+#[derive(Default)]
+struct Held {
+    keys: HashSet<KeyCode>,
+    mouse: HashSet<MouseButton>,
+    cursor: Option<Vec2>,
+}
+
+impl Held {
+    fn apply(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::CursorMoved(moved) => self.cursor = Some(moved.position),
+            WindowEvent::CursorLeft(_) => self.cursor = None,
+            WindowEvent::KeyboardInput(input) => {
+                return match input.state {
+                    ButtonState::Pressed => self.keys.insert(input.key_code),
+                    ButtonState::Released => self.keys.remove(&input.key_code),
+                };
+            },
+            WindowEvent::MouseButtonInput(input) => {
+                return match input.state {
+                    ButtonState::Pressed => self.mouse.insert(input.button),
+                    ButtonState::Released => self.mouse.remove(&input.button),
+                };
+            },
+            WindowEvent::KeyboardFocusLost(_) => {
+                self.keys.clear();
+                self.mouse.clear();
+                return true;
+            },
+            _ => {},
+        }
+        false
+    }
+}
+
+fn update(
+    mut events: MessageReader<WindowEvent>, mut held: Local<Held>,
+    mut actions: ResMut<ButtonInput<Action>>, mut action_events: ResMut<ActionEvents>,
+    bindings: Res<Bindings>,
+) {
+    actions.clear();
+    action_events.0.clear();
+    let mut order: Vec<_> = bindings.actions.iter().map(|(&a, &b)| (a, b)).collect();
+    order.sort_by_key(|(_, b)| !matches!(b, Binding::MousePair(..)));
+    for event in events.read() {
+        if !held.apply(event) {
+            continue;
+        }
+        for &(action, binding) in &order {
+            let pressed = binding.pressed(&held, bindings.left_handed);
+            if pressed == actions.pressed(action) {
+                continue;
+            }
+            if pressed {
+                actions.press(action);
+            } else {
+                actions.release(action);
+            }
+            let source =
+                if matches!(binding, Binding::Key(_)) { Source::Keyboard } else { Source::Mouse };
+            action_events.0.push(ActionEvent { action, pressed, cursor: held.cursor, source });
+        }
+    }
+}
+
+fn load(mut commands: Commands, bindings: Option<Res<Bindings>>) {
+    if bindings.is_some() {
+        return;
+    }
+    let mut bindings = Bindings::load();
+    for (action, binding) in Bindings::default().actions {
+        bindings.actions.entry(action).or_insert(binding);
+    }
+    commands.insert_resource(bindings);
+}
+
+pub fn actions(app: &mut App) {
+    app.add_systems(PreStartup, load)
+        .init_resource::<ButtonInput<Action>>()
+        .init_resource::<ActionEvents>()
+        .add_systems(PreUpdate, update);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebinding_to_a_used_binding_swaps_the_actions() {
+        let mut bindings = Bindings::default();
+        bindings.rebind(Action::Restart, Binding::Key(KeyCode::KeyP));
+        assert_eq!(bindings.actions[&Action::Restart], Binding::Key(KeyCode::KeyP));
+        assert_eq!(bindings.actions[&Action::Pause], Binding::Key(KeyCode::KeyR));
+
+        bindings.rebind(Action::Flag, Binding::Mouse(MouseButton::Left));
+        assert_eq!(bindings.actions[&Action::Reveal], Binding::Mouse(MouseButton::Right));
+        assert_eq!(bindings.actions[&Action::Pan], Binding::Mouse(MouseButton::Left));
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+use std::f32::consts::TAU;
+
+use bevy::camera::visibility::RenderLayers;
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::AppState;
+use crate::plugins::{
+    Board, Coordinates, Face, FlagPlaced, FlagRemoved, GameAssets, Preferences, RegionRevealed,
+    Theme, ThemeColor, Views,
+};
+use crate::utils::ProjectionExt;
+
+const RIPPLE_STEP: f32 = 0.03;
+const RIPPLE_DURATION: f32 = 0.75;
+const FLAG_DROP: f32 = 0.2;
+const MINE_STEP: f32 = 0.05;
+const MINE_DURATION: f32 = 1.5;
+const FIRE: [ThemeColor; 3] = [ThemeColor::Negative, ThemeColor::Hard, ThemeColor::Medium];
+const CONFETTI: [ThemeColor; 6] = [
+    ThemeColor::Easy,
+    ThemeColor::Medium,
+    ThemeColor::Hard,
+    ThemeColor::Extreme,
+    ThemeColor::Debug,
+    ThemeColor::Accent,
+];
+
+#[derive(Component, Default)]
+pub struct Animations {
+    hidden: HashMap<Coordinates, Face>,
+    queue: Vec<(f32, Coordinates)>,
+    explosion: Option<Coordinates>,
+}
+
+impl Animations {
+    #[must_use]
+    pub fn face(&self, board: &Board, coordinates: Coordinates) -> Face {
+        self.hidden.get(&coordinates).copied().unwrap_or_else(|| board.face(coordinates))
+    }
+
+    fn hide(&mut self, coordinates: Coordinates, face: Face, until: f32) {
+        self.hidden.insert(coordinates, face);
+        self.queue.push((until, coordinates));
+    }
+}
+
+#[derive(Component)]
+struct Particle {
+    velocity: Vec2,
+    gravity: f32,
+    spin: f32,
+    age: f32,
+    lifetime: f32,
+}
+
+#[derive(Component)]
+struct Drop {
+    board: Entity,
+    target: Vec2,
+    age: f32,
+}
+
+fn spawn_particles(
+    commands: &mut Commands, layers: &RenderLayers, count: usize, colors: &[Color],
+    mut particle: impl FnMut() -> (Vec2, Particle, f32),
+) {
+    let mut rng = rand::rng();
+    for _ in 0..count {
+        let (position, particle, size) = particle();
+        let color = colors[rng.random_range(0..colors.len())];
+        commands.spawn((
+            Sprite::from_color(color, Vec2::splat(size)),
+            Transform::from_translation(position.extend(1.0)),
+            particle,
+            layers.clone(),
+        ));
+    }
+}
+
+fn ripple(
+    revealed: On<RegionRevealed>, mut commands: Commands, time: Res<Time>,
+    preferences: Res<Preferences>, theme: Res<Theme>,
+    mut boards: Query<(&Board, &mut Animations, &RenderLayers)>,
+) {
+    if preferences.reduce_motion {
+        return;
+    }
+    let Ok((board, mut animations, layers)) = boards.get_mut(revealed.board) else { return };
+    let Some(&origin) = revealed.tiles.first() else { return };
+    let farthest = revealed.tiles.iter().map(|c| c.distance(origin)).max().unwrap_or(0);
+    let step = RIPPLE_STEP.min(RIPPLE_DURATION / farthest.max(1) as f32);
+    let now = time.elapsed_secs();
+    for &c in &revealed.tiles {
+        if board.face(c) == Face::BombClicked {
+            animations.explosion = Some(c);
+            let colors = FIRE.map(|slot| theme.color(slot));
+            let mut rng = rand::rng();
+            spawn_particles(&mut commands, layers, 48, &colors, || {
+                let direction = Vec2::from_angle(rng.random_range(0.0..TAU));
+                let particle = Particle {
+                    velocity: direction * rng.random_range(2.0..7.0),
+                    gravity: -12.0,
+                    spin: rng.random_range(-8.0..8.0),
+                    age: 0.0,
+                    lifetime: rng.random_range(0.5..1.1),
+                };
+                (c.center(), particle, rng.random_range(0.12..0.3))
+            });
+        } else if c != origin {
+            animations.hide(c, Face::Covered, now + c.distance(origin) as f32 * step);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn drop_flag(
+    placed: On<FlagPlaced>, mut commands: Commands, assets: Res<GameAssets>,
+    images: Res<Assets<Image>>, time: Res<Time>, preferences: Res<Preferences>, theme: Res<Theme>,
+    mut boards: Query<(&mut Animations, &RenderLayers)>,
+) {
+    if preferences.reduce_motion {
+        return;
+    }
+    let Ok((mut animations, layers)) = boards.get_mut(placed.board) else { return };
+    let coordinates = placed.coordinates;
+    animations.hide(coordinates, Face::Covered, time.elapsed_secs() + FLAG_DROP);
+    let sprite = match assets.sprite(Face::Flagged, &images) {
+        Some(sprite) => Sprite { custom_size: Some(Vec2::ONE), ..sprite },
+        None => Sprite::from_color(theme.color(ThemeColor::Negative), Vec2::splat(0.5)),
+    };
+    commands.spawn((
+        sprite,
+        Transform::from_translation(coordinates.center().extend(1.0)),
+        Drop { board: placed.board, target: coordinates.center(), age: 0.0 },
+        layers.clone(),
+    ));
+}
+
+fn lift_flag(
+    removed: On<FlagRemoved>, mut commands: Commands, mut boards: Query<&mut Animations>,
+    drops: Query<(Entity, &Drop)>,
+) {
+    let coordinates = removed.coordinates;
+    if let Ok(mut animations) = boards.get_mut(removed.board) {
+        animations.hidden.remove(&coordinates);
+    }
+    for (entity, drop) in drops {
+        if drop.board == removed.board && drop.target == coordinates.center() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn stagger_mines(
+    boards: Query<(&Board, &mut Animations)>, time: Res<Time>, preferences: Res<Preferences>,
+) {
+    if preferences.reduce_motion {
+        return;
+    }
+    for (board, mut animations) in boards {
+        if !board.is_cleared() {
+            stagger(board, &mut animations, time.elapsed_secs());
+        }
+    }
+}
+
+fn stagger(board: &Board, animations: &mut Animations, now: f32) {
+    let mut mines: Vec<_> = board
+        .coordinates()
+        .filter(|&c| matches!(board.face(c), Face::Bomb | Face::WrongFlag))
+        .collect();
+    let origin = animations.explosion.take().or_else(|| mines.first().copied());
+    let Some(origin) = origin else { return };
+    mines.sort_by_key(|c| c.distance(origin));
+    let step = MINE_STEP.min(MINE_DURATION / mines.len().max(1) as f32);
+    let start = now + 0.3;
+    for (i, c) in mines.into_iter().enumerate() {
+        let face = if board.face(c) == Face::WrongFlag { Face::Flagged } else { Face::Covered };
+        animations.hide(c, face, start + i as f32 * step);
+    }
+}
+
+fn celebrate(
+    mut commands: Commands, cameras: Query<(&Transform, &Projection, &Views, &RenderLayers)>,
+    boards: Query<&Board>, preferences: Res<Preferences>, theme: Res<Theme>,
+) {
+    if preferences.reduce_motion {
+        return;
+    }
+    for (transform, projection, views, layers) in cameras {
+        if boards.get(views.0).is_ok_and(Board::is_cleared) {
+            confetti(&mut commands, transform, projection, layers, &theme);
+        }
+    }
+}
+
+fn confetti(
+    commands: &mut Commands, transform: &Transform, projection: &Projection, layers: &RenderLayers,
+    theme: &Theme,
+) {
+    let Some(orthographic) = projection.as_orthographic() else { return };
+    let area = orthographic.area;
+    let center = transform.translation.truncate();
+    let unit = area.height() / 20.0;
+    let colors = CONFETTI.map(|slot| theme.color(slot));
+    let mut rng = rand::rng();
+    spawn_particles(commands, layers, 150, &colors, || {
+        let x = rng.random_range(area.min.x..area.max.x);
+        let y = area.max.y + rng.random_range(0.0..unit * 2.0);
+        let particle = Particle {
+            velocity: Vec2::new(rng.random_range(-2.0..2.0), rng.random_range(-4.0..-1.0)) * unit,
+            gravity: -2.0 * unit,
+            spin: rng.random_range(-6.0..6.0),
+            age: 0.0,
+            lifetime: rng.random_range(2.0..3.5),
+        };
+        (center + Vec2::new(x, y), particle, unit * rng.random_range(0.2..0.4))
+    });
+}
+
+fn tick(time: Res<Time>, boards: Query<(&mut Animations, &mut Board)>) {
+    let now = time.elapsed_secs();
+    for (mut animations, mut board) in boards {
+        if animations.queue.is_empty() {
+            continue;
+        }
+        let Animations { hidden, queue, .. } = &mut *animations;
+        queue.retain(|&(until, c)| {
+            if until > now {
+                return true;
+            }
+            hidden.remove(&c);
+            board.touch(c);
+            false
+        });
+    }
+}
+
+fn update_particles(
+    mut commands: Commands, time: Res<Time>,
+    particles: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+) {
+    let delta = time.delta_secs();
+    for (entity, mut particle, mut transform, mut sprite) in particles {
+        particle.age += delta;
+        if particle.age >= particle.lifetime {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        particle.velocity.y += particle.gravity * delta;
+        transform.translation += (particle.velocity * delta).extend(0.0);
+        transform.rotate_z(particle.spin * delta);
+        sprite.color.set_alpha(1.0 - particle.age / particle.lifetime);
+    }
+}
+
+fn update_drops(
+    mut commands: Commands, time: Res<Time>, drops: Query<(Entity, &mut Drop, &mut Transform)>,
+) {
+    for (entity, mut drop, mut transform) in drops {
+        drop.age += time.delta_secs();
+        if drop.age >= FLAG_DROP {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let eased = 1.0 - (1.0 - drop.age / FLAG_DROP).powi(2);
+        transform.translation = (drop.target + Vec2::Y * (1.0 - eased) * 1.5).extend(1.0);
+        transform.scale = Vec3::splat(1.5 - 0.5 * eased);
+    }
+}
+
+fn reset(
+    mut commands: Commands, boards: Query<Entity, With<Board>>,
+    effects: Query<Entity, Or<(With<Particle>, With<Drop>)>>,
+) {
+    for entity in boards {
+        commands.entity(entity).insert(Animations::default());
+    }
+    for entity in effects {
+        commands.entity(entity).despawn();
+    }
+}
+
+pub fn animations(app: &mut App) {
+    app.add_observer(ripple)
+        .add_observer(drop_flag)
+        .add_observer(lift_flag)
+        .add_systems(OnEnter(AppState::Playing), reset)
+        .add_systems(OnEnter(AppState::Menu), reset)
+        .add_systems(
+            Update,
+            (
+                stagger_mines.run_if(
+                    in_state(AppState::Won)
+                        .or(in_state(AppState::Lost))
+                        .and(state_changed::<AppState>),
+                ),
+                celebrate.run_if(in_state(AppState::Won).and(state_changed::<AppState>)),
+                tick,
+                update_particles,
+                update_drops,
+            )
+                .chain(),
+        );
+}
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use std::fs;
+
+use bevy::asset::io::file::FileAssetReader;
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::AppState;
+use crate::plugins::{Board, Chorded, Face, FlagPlaced, FlagRemoved, Preferences, RegionRevealed};
+
+const EXTENSIONS: [&str; 2] = ["ogg", "wav"];
+const FLOOD_SIZE: f32 = 500.0;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+enum Sound {
+    Reveal,
+    Flood,
+    Flag,
+    Unflag,
+    Chord,
+    InvalidChord,
+    Win,
+    Loss,
+}
+
+impl Sound {
+    const ALL: [Self; 8] = [
+        Self::Reveal,
+        Self::Flood,
+        Self::Flag,
+        Self::Unflag,
+        Self::Chord,
+        Self::InvalidChord,
+        Self::Win,
+        Self::Loss,
+    ];
+
+    fn file(self) -> &'static str {
+        match self {
+            Sound::Reveal => "reveal",
+            Sound::Flood => "flood",
+            Sound::Flag => "flag",
+            Sound::Unflag => "unflag",
+            Sound::Chord => "chord",
+            Sound::InvalidChord => "invalid_chord",
+            Sound::Win => "win",
+            Sound::Loss => "loss",
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Manifest {
+    name: Option<String>,
+}
+
+pub struct SoundPack {
+    pub name: String,
+    files: HashMap<Sound, String>,
+}
+
+impl SoundPack {
+    fn load(&self, asset_server: &AssetServer) -> HashMap<Sound, Handle<AudioSource>> {
+        self.files.iter().map(|(&sound, file)| (sound, asset_server.load(file))).collect()
+    }
+}
+
+#[derive(Resource)]
+pub struct SoundPacks(Vec<SoundPack>);
+
+impl SoundPacks {
+    fn discover() -> Self {
+        let root = FileAssetReader::get_base_path().join("assets").join("sounds");
+        let mut packs: Vec<_> = fs::read_dir(&root)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| {
+                let dir = entry.file_name().to_string_lossy().into_owned();
+                let path = entry.path().join("pack.ron");
+                let manifest = fs::read_to_string(&path).map_or_else(
+                    |_| Manifest::default(),
+                    |text| {
+                        ron::from_str(&text).unwrap_or_else(|e| {
+                            warn!("Failed to parse {}: {e}", path.display());
+                            Manifest::default()
+                        })
+                    },
+                );
+                let files = Sound::ALL
+                    .into_iter()
+                    .filter_map(|sound| {
+                        let file = EXTENSIONS
+                            .iter()
+                            .map(|extension| format!("{}.{extension}", sound.file()))
+                            .find(|file| entry.path().join(file).is_file())?;
+                        Some((sound, format!("sounds/{dir}/{file}")))
+                    })
+                    .collect();
+                SoundPack { name: manifest.name.unwrap_or(dir), files }
+            })
+            .collect();
+        if packs.is_empty() {
+            warn!("No sound packs found in {}", root.display());
+            packs.push(SoundPack { name: String::from("Silent"), files: HashMap::new() });
+        }
+        packs.sort_by(|a, b| a.name.cmp(&b.name));
+        Self(packs)
+    }
+
+    #[must_use]
+    pub fn get(&self, name: &str) -> &SoundPack {
+        self.0.iter().find(|p| p.name == name).unwrap_or(&self.0[0])
+    }
+
+    #[must_use]
+    pub fn next(&self, name: &str) -> &SoundPack {
+        let index = self.0.iter().position(|p| p.name == name).map_or(0, |i| i + 1);
+        &self.0[index % self.0.len()]
+    }
+}
+
+#[derive(Resource)]
+pub struct GameSounds {
+    pub pack: String,
+    sounds: HashMap<Sound, Handle<AudioSource>>,
+}
+
+impl GameSounds {
+    fn play(
+        &self, commands: &mut Commands, preferences: &Preferences, sound: Sound, volume: f32,
+        speed: f32,
+    ) {
+        if preferences.muted || preferences.volume == 0 {
+            return;
+        }
+        let Some(handle) = self.sounds.get(&sound) else { return };
+        let volume = Volume::Linear(volume * f32::from(preferences.volume) / 100.0);
+        commands.spawn((
+            AudioPlayer(handle.clone()),
+            PlaybackSettings::DESPAWN.with_volume(volume).with_speed(speed),
+        ));
+    }
+}
+
+fn select(
+    mut commands: Commands, asset_server: Res<AssetServer>, packs: Res<SoundPacks>,
+    preferences: Res<Preferences>, sounds: Option<Res<GameSounds>>,
+) {
+    if sounds.is_some_and(|s| s.pack == preferences.sound_pack) {
+        return;
+    }
+    let pack = packs.get(&preferences.sound_pack);
+    commands
+        .insert_resource(GameSounds { pack: pack.name.clone(), sounds: pack.load(&asset_server) });
+}
+
+fn revealed(
+    revealed: On<RegionRevealed>, mut commands: Commands, boards: Query<&Board>,
+    sounds: Res<GameSounds>, preferences: Res<Preferences>,
+) {
+    let Ok(board) = boards.get(revealed.board) else { return };
+    if revealed.tiles.iter().any(|&c| board.face(c) == Face::BombClicked) {
+        return;
+    }
+    if let [_] = revealed.tiles.as_slice() {
+        sounds.play(&mut commands, &preferences, Sound::Reveal, 1.0, 1.0);
+    } else {
+        let size = (revealed.tiles.len() as f32).ln() / FLOOD_SIZE.ln();
+        let size = size.min(1.0);
+        sounds.play(&mut commands, &preferences, Sound::Flood, 0.6 + 0.4 * size, 1.15 - 0.3 * size);
+    }
+}
+
+fn flag_placed(
+    _: On<FlagPlaced>, mut commands: Commands, sounds: Res<GameSounds>,
+    preferences: Res<Preferences>,
+) {
+    sounds.play(&mut commands, &preferences, Sound::Flag, 1.0, 1.0);
+}
+
+fn flag_removed(
+    _: On<FlagRemoved>, mut commands: Commands, sounds: Res<GameSounds>,
+    preferences: Res<Preferences>,
+) {
+    sounds.play(&mut commands, &preferences, Sound::Unflag, 1.0, 1.0);
+}
+
+fn chorded(
+    chorded: On<Chorded>, mut commands: Commands, boards: Query<&Board>, sounds: Res<GameSounds>,
+    preferences: Res<Preferences>,
+) {
+    let Ok(board) = boards.get(chorded.board) else { return };
+    let sound =
+        if board.can_chord(chorded.coordinates) { Sound::Chord } else { Sound::InvalidChord };
+    sounds.play(&mut commands, &preferences, sound, 1.0, 1.0);
+}
+
+fn won(mut commands: Commands, sounds: Res<GameSounds>, preferences: Res<Preferences>) {
+    sounds.play(&mut commands, &preferences, Sound::Win, 1.0, 1.0);
+}
+
+fn lost(mut commands: Commands, sounds: Res<GameSounds>, preferences: Res<Preferences>) {
+    sounds.play(&mut commands, &preferences, Sound::Loss, 1.0, 1.0);
+}
+
+pub fn audio(app: &mut App) {
+    app.insert_resource(SoundPacks::discover())
+        .add_systems(PreUpdate, select.run_if(resource_changed::<Preferences>))
+        .add_observer(revealed)
+        .add_observer(flag_placed)
+        .add_observer(flag_removed)
+        .add_observer(chorded)
+        .add_systems(OnEnter(AppState::Won), won)
+        .add_systems(OnEnter(AppState::Lost), lost);
+}
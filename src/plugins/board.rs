@@ -1,311 +1,240 @@
 use bevy::prelude::*;
-use bevy::sprite::Anchor;
-use itertools::iproduct;
-use rand::Rng;
 
-use crate::AppState;
-use crate::plugins::{Difficulty, GameAssets, LeftClicked, MineCount, RightClicked, Size};
+use crate::plugins::{
+    Action, Bindings, Chorded, LeftClicked, PressPreview, Remote, Replay, RightClicked, Source,
+    Turns, unranked,
+};
+use crate::rules::{Board, Click, Clicks, Coordinates, Face, Play};
+use crate::{AppState, Pause};
 
-const OFFSETS: [(isize, isize); 8] =
-    [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+type Rules = (Without<Remote>, Without<Turns>);
 
-#[derive(Resource)]
-pub struct Board {
-    pub width: usize,
-    pub height: usize,
-    pub mines: usize,
-    debug: bool,
-    pub difficulty: Difficulty,
-    pub size: Size,
-    tiles: Box<[Box<[Entity]>]>,
+impl Coordinates {
+    #[must_use]
+    pub fn center(self) -> Vec2 { Vec2::new(self.x as f32 + 0.5, -(self.y as f32) - 0.5) }
 }
 
 impl Board {
-    pub fn new(size: Size, difficulty: Difficulty) -> Self {
-        let (width, height) = size.dimensions();
-        let mines = difficulty.mine_count(width * height);
-        let debug = difficulty.is_debug();
-        Self { width, height, mines, debug, difficulty, size, tiles: Box::new([]) }
+    #[must_use]
+    pub fn bounds(&self) -> Rect {
+        self.extent().map_or_else(Rect::default, |(min, max)| {
+            Rect::new(min.x as f32, -max.y as f32, max.x as f32, -min.y as f32)
+        })
     }
 
-    pub fn size(&self) -> Vec2 { Vec2::new(self.width as _, self.height as _) }
-
-    pub fn get_from_world(&self, v: Vec2) -> Option<Entity> {
-        if v.x >= 0.0 && v.y <= 0.0 {
-            self.tiles.get(v.x as usize).and_then(|c| c.get(-v.y as usize)).copied()
+    #[must_use]
+    pub fn initial_view(&self) -> Rect {
+        if self.is_endless() {
+            Rect::from_center_size(Vec2::new(0.5, -0.5), Vec2::new(32.0, 18.0))
         } else {
-            None
+            self.bounds().inflate(0.25)
         }
     }
 
-    fn get_neighbours(&self, coordinates: Coordinates) -> impl Iterator<Item = Entity> {
-        OFFSETS.into_iter().filter_map(move |(dx, dy)| {
-            self.tiles
-                .get(coordinates.x.checked_add_signed(dx)?)?
-                .get(coordinates.y.checked_add_signed(dy)?)
-                .copied()
-        })
+    #[must_use]
+    pub fn get_from_world(&self, v: Vec2) -> Option<Coordinates> {
+        let coordinates = Coordinates::new(v.x.floor() as i32, (-v.y).floor() as i32);
+        self.contains(coordinates).then_some(coordinates)
     }
 }
 
-fn set_mine(temp: &mut [Vec<i8>], x: usize, y: usize) {
-    temp[x][y] = -1;
-    for (xd, yd) in OFFSETS {
-        if let Some(x) = x.checked_add_signed(xd)
-            && let Some(c) = temp.get_mut(x)
-            && let Some(y) = y.checked_add_signed(yd)
-            && let Some(n) = c.get_mut(y)
-            && *n != -1
-        {
-            *n += 1;
-        }
-    }
-}
-
-fn initialize(mut board: ResMut<Board>, assets: Res<GameAssets>, mut commands: Commands) {
-    if !board.tiles.is_empty() {
-        board.tiles.iter().flatten().for_each(|&e| commands.entity(e).despawn());
-    }
-    let mut temp = vec![vec![0; board.height]; board.width];
-    if board.debug {
-        iproduct!([1usize, 4, 7], [1usize, 4, 7])
-            .enumerate()
-            .flat_map(|(n, (x, y))| {
-                OFFSETS
-                    .into_iter()
-                    .take(n)
-                    .map(move |(xd, yd)| (x.strict_add_signed(xd), y.strict_add_signed(yd)))
-            })
-            .for_each(|(x, y)| set_mine(&mut temp, x, y));
-    } else {
-        let mut chosen = 0;
-        let mut rand = rand::rng();
-        while chosen < board.mines {
-            let x = rand.random_range(0..board.width);
-            let y = rand.random_range(0..board.height);
-            if temp[x][y] >= 0 {
-                set_mine(&mut temp, x, y);
-                chosen += 1;
-            }
+fn initialize(
+    mut commands: Commands, boards: Query<(Entity, &mut Board, Has<Turns>), Without<Remote>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let random = rand::random();
+    for (entity, mut board, turns) in boards {
+        let seed = board.preset_seed().unwrap_or(random);
+        board.start_seeded(seed);
+        commands.entity(entity).insert(Clicks::default());
+        if board.is_cleared() && !turns {
+            next_state.set(AppState::Won);
         }
     }
-    board.tiles = temp
-        .into_iter()
-        .enumerate()
-        .map(|(x, c)| {
-            c.into_iter()
-                .enumerate()
-                .map(|(y, n)| Tile::spawn(x, y, n, &mut commands, &assets))
-                .collect()
-        })
-        .collect();
-}
-
-#[derive(Component)]
-#[require(Sprite, Coordinates, TileState, TileValue)]
-struct Tile;
-
-impl Tile {
-    fn spawn(
-        x: usize, y: usize, bombs: i8, commands: &mut Commands, assets: &GameAssets,
-    ) -> Entity {
-        commands
-            .spawn((
-                Tile,
-                Sprite {
-                    image: assets.covered.clone(),
-                    custom_size: Some(Vec2::new(1.0, 1.0)),
-                    image_mode: SpriteImageMode::Scale(ScalingMode::FitCenter),
-                    ..default()
-                },
-                Transform::from_translation(Vec3::new(x as f32, -(y as f32), 0.0)),
-                Anchor::TOP_LEFT,
-                Coordinates::new(x, y),
-                TileValue::from_i8(bombs),
-                DespawnOnEnter(AppState::Menu)
-            ))
-            .id()
-    }
-}
-
-#[derive(Component, Default, Copy, Clone)]
-struct Coordinates {
-    x: usize,
-    y: usize,
 }
 
-impl Coordinates {
-    fn new(x: usize, y: usize) -> Self { Self { x, y } }
-}
-
-#[derive(Component, Default, PartialEq, Eq, Clone, Copy)]
-enum TileState {
-    #[default]
-    Covered,
-    Flagged,
-    Uncovered,
-}
-
-#[derive(Component, Default, PartialEq, Eq, Clone, Copy)]
-enum TileValue {
-    #[default]
-    Empty,
-    Neighbours(u8),
-    Bomb,
+#[derive(EntityEvent)]
+pub struct RegionRevealed {
+    #[event_target]
+    pub board: Entity,
+    pub tiles: Vec<Coordinates>,
 }
 
-impl TileValue {
-    fn get_image(self, assets: &GameAssets) -> Handle<Image> {
-        match self {
-            Self::Empty => assets.empty.clone(),
-            Self::Neighbours(n) => assets.neighbours[(n - 1) as usize].clone(),
-            Self::Bomb => assets.bomb_clicked.clone(),
-        }
-    }
-
-    fn from_i8(v: i8) -> Self {
-        match v {
-            -1 => Self::Bomb,
-            0 => Self::Empty,
-            1..=8 => Self::Neighbours(v as _),
-            _ => panic!("Unexpected value"),
-        }
-    }
+#[derive(EntityEvent)]
+pub struct FlagPlaced {
+    #[event_target]
+    pub board: Entity,
+    pub coordinates: Coordinates,
 }
 
 #[derive(EntityEvent)]
-struct Flood {
-    entity: Entity,
+pub struct FlagRemoved {
+    #[event_target]
+    pub board: Entity,
+    pub coordinates: Coordinates,
 }
 
-fn uncover(
-    sprite: &mut Sprite, state: &mut TileState, value: TileValue, coordinates: Coordinates,
-    commands: &mut Commands, assets: &GameAssets, board: &Board,
-) {
-    sprite.image = value.get_image(assets);
-    *state = TileState::Uncovered;
-    match value {
-        TileValue::Empty => mark_neighbours(commands, board, coordinates),
-        TileValue::Bomb => commands.set_state(AppState::Lost),
-        TileValue::Neighbours(_) => {},
+fn revealed(commands: &mut Commands, board: Entity, tiles: Vec<Coordinates>) {
+    if !tiles.is_empty() {
+        commands.trigger(RegionRevealed { board, tiles });
     }
 }
 
-fn mark_neighbours(commands: &mut Commands, board: &Board, coordinates: Coordinates) {
-    for entity in board.get_neighbours(coordinates) {
-        commands.trigger(Flood { entity });
+fn apply(commands: &mut Commands, board: Entity, state: &Board, play: Play) {
+    let flag = |commands: &mut Commands, coordinates| match state.face(coordinates) {
+        Face::Flagged => commands.trigger(FlagPlaced { board, coordinates }),
+        Face::Covered => commands.trigger(FlagRemoved { board, coordinates }),
+        _ => {},
+    };
+    match play {
+        Play::Reveal(tiles) => revealed(commands, board, tiles),
+        Play::Chord => {},
+        Play::Flag(coordinates) => flag(commands, coordinates),
+        Play::FlagChord(tiles) => {
+            for coordinates in tiles {
+                flag(commands, coordinates);
+            }
+        },
     }
 }
 
 fn left_click(
     left_clicked: On<LeftClicked>, mut commands: Commands,
-    mut tiles: Query<(&mut Sprite, &mut TileState, &TileValue, &Coordinates), With<Tile>>,
-    board: Res<Board>, assets: Res<GameAssets>,
+    mut boards: Query<(&mut Board, &mut Clicks), Rules>,
 ) {
-    let Ok((mut sprite, mut state, &value, &coordinates)) =
-        tiles.get_mut(left_clicked.event_target())
-    else {
-        return;
-    };
-    match *state {
-        TileState::Covered => {
-            uncover(&mut sprite, &mut state, value, coordinates, &mut commands, &assets, &board);
-        },
-        TileState::Uncovered => match value {
-            TileValue::Neighbours(n) => {
-                if board
-                    .get_neighbours(coordinates)
-                    .filter(|&e| *tiles.get(e).unwrap().1 == TileState::Flagged)
-                    .count()
-                    == n as usize
-                {
-                    mark_neighbours(&mut commands, &board, coordinates);
-                }
-            },
-            TileValue::Empty | TileValue::Bomb => {},
-        },
-        TileState::Flagged => {},
+    let (entity, coordinates) = (left_clicked.board, left_clicked.coordinates);
+    let Ok((mut board, mut clicks)) = boards.get_mut(entity) else { return };
+    let Some(left) = board.left_click(coordinates) else { return };
+    clicks.count(Click::Left, &left);
+    if left == Play::Chord {
+        commands.trigger(Chorded { board: entity, coordinates, source: Source::Rules });
     }
+    apply(&mut commands, entity, &board, left);
 }
 
-fn flood(
-    flood: On<Flood>, mut commands: Commands,
-    mut tiles: Query<(&mut Sprite, &mut TileState, &TileValue, &Coordinates), With<Tile>>,
-    board: Res<Board>, assets: Res<GameAssets>,
+fn chord(
+    chorded: On<Chorded>, mut commands: Commands,
+    mut boards: Query<(&mut Board, &mut Clicks), Rules>,
 ) {
-    let Ok((mut sprite, mut state, &value, &coordinates)) = tiles.get_mut(flood.event_target())
-    else {
-        return;
-    };
-    if *state == TileState::Covered {
-        uncover(&mut sprite, &mut state, value, coordinates, &mut commands, &assets, &board);
+    let (entity, coordinates) = (chorded.board, chorded.coordinates);
+    let Ok((mut board, mut clicks)) = boards.get_mut(entity) else { return };
+    if let Some(chord) = board.chord_click(coordinates) {
+        clicks.count(Click::Chord, &chord);
+        apply(&mut commands, entity, &board, chord);
     }
 }
 
 fn right_click(
-    right_clicked: On<RightClicked>, mut tiles: Query<(&mut Sprite, &mut TileState), With<Tile>>,
-    assets: Res<GameAssets>, mut count: ResMut<MineCount>,
+    right_clicked: On<RightClicked>, mut commands: Commands,
+    mut boards: Query<(&mut Board, &mut Clicks), Rules>, bindings: Res<Bindings>,
 ) {
-    let Ok((mut sprite, mut state)) = tiles.get_mut(right_clicked.event_target()) else {
-        return;
-    };
-    match *state {
-        TileState::Covered => {
-            sprite.image = assets.flagged.clone();
-            *state = TileState::Flagged;
-            count.0 -= 1;
-        },
-        TileState::Flagged => {
-            sprite.image = assets.covered.clone();
-            *state = TileState::Covered;
-            count.0 += 1;
-        },
-        TileState::Uncovered => {},
-    }
+    let (entity, coordinates) = (right_clicked.board, right_clicked.coordinates);
+    let Ok((mut board, mut clicks)) = boards.get_mut(entity) else { return };
+    let Some(right) = board.right_click(coordinates, bindings.flag_chord) else { return };
+    clicks.count(Click::Right, &right);
+    apply(&mut commands, entity, &board, right);
 }
 
-fn check_win(
-    changed: Query<(), (Changed<TileState>, With<Tile>)>, tiles: Query<&TileState, With<Tile>>,
-    board: Res<Board>, mut next_state: ResMut<NextState<AppState>>,
+fn region_revealed(
+    revealed: On<RegionRevealed>, boards: Query<&Board, Without<Turns>>,
+    mut next_state: ResMut<NextState<AppState>>,
 ) {
-    if !changed.is_empty()
-        && tiles.into_iter().filter(|&&s| s != TileState::Uncovered).count() == board.mines
-    {
+    let Ok(board) = boards.get(revealed.board) else { return };
+    if board.is_cleared() {
         next_state.set(AppState::Won);
+    } else if boards.iter().all(Board::is_exploded) {
+        next_state.set(AppState::Lost);
     }
 }
 
-fn add_flags(
-    tiles: Query<(&mut Sprite, &TileState, &TileValue), With<Tile>>, assets: Res<GameAssets>,
+fn hint(
+    mut commands: Commands, actions: Res<ButtonInput<Action>>,
+    board: Single<(Entity, &mut Board), Rules>,
 ) {
-    for (mut sprite, &state, &value) in tiles {
-        if state == TileState::Covered && value == TileValue::Bomb {
-            sprite.image = assets.flagged.clone();
+    if !actions.just_pressed(Action::Hint) {
+        return;
+    }
+    let (entity, mut board) = board.into_inner();
+    if let Some(coordinates) = board.hint() {
+        revealed(&mut commands, entity, board.reveal([coordinates]));
+    }
+}
+
+fn press_preview(boards: Query<(&PressPreview, &mut Board)>) {
+    for (preview, mut board) in boards {
+        let mut desired = Vec::new();
+        if let Some(coordinates) = preview.coordinates
+            && board.contains(coordinates)
+        {
+            desired.push(coordinates);
+            if preview.chord || matches!(board.face(coordinates), Face::Neighbours(_)) {
+                desired.extend(board.get_neighbours(coordinates));
+            }
+        }
+        if desired != board.pressed() {
+            board.set_pressed(desired);
         }
     }
 }
 
-fn uncover_bombs(
-    tiles: Query<(&mut Sprite, &TileState, &TileValue), With<Tile>>, assets: Res<GameAssets>,
-) {
-    for (mut sprite, &state, &value) in tiles {
-        if state == TileState::Covered && value == TileValue::Bomb {
-            sprite.image = assets.bomb.clone();
+fn clear_pressed(boards: Query<&mut Board>) {
+    for mut board in boards {
+        board.set_pressed(Vec::new());
+    }
+}
+
+fn add_flags(boards: Query<&mut Board, Without<Remote>>) {
+    for mut board in boards {
+        if board.is_cleared() {
+            board.finish(true);
+        } else if !board.is_exploded() {
+            board.finish(false);
         }
     }
 }
 
-fn despawn(mut commands: Commands) {
-    commands.remove_resource::<Board>();
+fn uncover_bombs(boards: Query<&mut Board, Without<Remote>>) {
+    for mut board in boards {
+        board.finish(false);
+    }
 }
 
 pub fn board(app: &mut App) {
     app.add_systems(OnEnter(AppState::Playing), initialize)
-        .add_systems(Update, check_win.run_if(in_state(AppState::Playing)))
+        .add_systems(
+            Update,
+            hint.run_if(in_state(Pause::Running).and(not(resource_exists::<Replay>)).and(unranked)),
+        )
+        .add_systems(Update, press_preview.run_if(in_state(AppState::Playing)))
+        .add_systems(OnExit(AppState::Playing), clear_pressed)
         .add_observer(left_click)
+        .add_observer(chord)
         .add_observer(right_click)
-        .add_observer(flood)
+        .add_observer(region_revealed)
         .add_systems(OnEnter(AppState::Won), add_flags)
-        .add_systems(OnEnter(AppState::Lost), uncover_bombs)
-        .add_systems(OnEnter(AppState::Menu), despawn.run_if(resource_exists::<Board>));
+        .add_systems(OnEnter(AppState::Lost), uncover_bombs);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+    use crate::rules::{Difficulty, Layout};
+
+    #[test]
+    fn imported_boards_without_covered_safe_cells_are_won() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin))
+            .init_state::<AppState>()
+            .add_sub_state::<Pause>()
+            .init_resource::<ButtonInput<Action>>()
+            .add_plugins(board);
+        let layout = Layout::parse("*1\n11\n").unwrap();
+        app.world_mut().spawn(Board::from_layout(layout, Difficulty::Easy));
+        app.world_mut().resource_mut::<NextState<AppState>>().set(AppState::Playing);
+        app.update();
+        app.update();
+        assert_eq!(**app.world().resource::<State<AppState>>(), AppState::Won);
+    }
 }
@@ -1,44 +1,56 @@
 use std::ops::RangeInclusive;
 
+use bevy::camera::Viewport;
+use bevy::camera::visibility::RenderLayers;
 use bevy::prelude::*;
 use bevy::window::WindowResized;
 
 use crate::AppState;
-use crate::plugins::Board;
+use crate::plugins::{Board, Player};
 use crate::utils::ProjectionExt;
 
 #[derive(Component)]
 pub struct MainCamera;
 
-fn spawn_main(mut commands: Commands) { commands.spawn((Camera2d, MainCamera)); }
+#[derive(Component)]
+pub struct Views(pub Entity);
+
+fn spawn_main(mut commands: Commands) { commands.spawn((Camera2d, MainCamera, IsDefaultUiCamera)); }
 
-#[derive(Resource)]
+#[derive(Component)]
 pub struct CameraLimits {
+    bounds: Rect,
     horizontal: RangeInclusive<f32>,
     vertical: RangeInclusive<f32>,
     scale: RangeInclusive<f32>,
 }
 
 impl Default for CameraLimits {
-    fn default() -> Self { Self { horizontal: 0.0..=0.0, vertical: 0.0..=0.0, scale: 0.0..=0.0 } }
+    fn default() -> Self {
+        Self {
+            bounds: Rect::default(),
+            horizontal: 0.0..=0.0,
+            vertical: 0.0..=0.0,
+            scale: 0.0..=0.0,
+        }
+    }
 }
 
 impl CameraLimits {
-    fn calculate_scale_limits(&mut self, board_size: Vec2, window_size: Vec2) {
+    fn calculate_scale_limits(&mut self, window_size: Vec2) {
         let min_board = Vec2::splat(3.0);
-        let max_board = board_size + 0.5;
+        let max_board = self.bounds.size() + 0.5;
         let min_scales = min_board / window_size;
         let max_scales = max_board / window_size;
         self.scale = min_scales.max_element()..=max_scales.max_element();
     }
 
-    fn calculate_translation_limits(&mut self, board_size: Vec2, window_size: Vec2, zoom: f32) {
-        let board_min = Vec2::new(-0.25, -board_size.y - 0.25);
-        let board_max = Vec2::new(board_size.x + 0.25, 0.25);
-        let center = Vec2::new(board_size.x / 2.0, -board_size.y / 2.0);
+    fn calculate_translation_limits(&mut self, window_size: Vec2, zoom: f32) {
+        let board = self.bounds.inflate(0.25);
+        let center = self.bounds.center();
         let half_view = window_size / 2.0 * zoom;
-        let cam_max = board_max - half_view;
-        let cam_min = board_min + half_view;
+        let cam_max = board.max - half_view;
+        let cam_min = board.min + half_view;
         self.horizontal =
             if cam_min.x <= cam_max.x { cam_min.x..=cam_max.x } else { center.x..=center.x };
         self.vertical =
@@ -58,51 +70,136 @@ impl CameraLimits {
     }
 }
 
-fn spawn(
-    camera: Single<(&mut Transform, &mut Projection), With<MainCamera>>, window: Single<&Window>,
-    board: Res<Board>, mut commands: Commands,
+fn view_size(camera: &Camera, window: &Window) -> Vec2 {
+    camera
+        .viewport
+        .as_ref()
+        .map_or(window.size(), |viewport| viewport.physical_size.as_vec2() / window.scale_factor())
+}
+
+fn fit_view(
+    (transform, projection, camera, limits): (
+        &mut Transform,
+        &mut Projection,
+        &Camera,
+        &mut CameraLimits,
+    ),
+    window: &Window, board: &Board, view: Rect,
 ) {
-    let (mut transform, mut projection) = camera.into_inner();
     let orthographic = projection.as_orthographic_mut().unwrap();
-    let board_size = board.size();
-    let window_size = window.size();
-    let mut limits = CameraLimits::default();
-    limits.calculate_scale_limits(board_size, window_size);
-    orthographic.scale = *limits.scale.end();
-    limits.calculate_translation_limits(board_size, window_size, orthographic.scale);
+    let view_size = view_size(camera, window);
+    *limits = CameraLimits { bounds: board.bounds(), ..default() };
+    limits.calculate_scale_limits(view_size);
+    orthographic.scale = (view.size() / view_size).max_element();
+    limits.limit_scale(&mut orthographic.scale);
+    limits.calculate_translation_limits(view_size, orthographic.scale);
+    transform.translation = view.center().extend(transform.translation.z);
     limits.limit_translation(&mut transform.translation);
-    commands.insert_resource(limits);
+}
+
+fn arrange(
+    mut cameras: Query<(&mut Camera, &Views)>, window: Single<&Window>, players: Query<&Player>,
+) {
+    let count = cameras.iter().count() as u32;
+    let size = window.physical_size();
+    for (mut camera, views) in &mut cameras {
+        camera.viewport = (count > 1).then(|| {
+            let index = players.get(views.0).map_or(0, |player| player.0 as u32);
+            let width = size.x / count;
+            Viewport {
+                physical_position: UVec2::new(index * width, 0),
+                physical_size: UVec2::new(width, size.y),
+                ..default()
+            }
+        });
+    }
+}
+
+fn spawn(
+    cameras: Query<(&mut Transform, &mut Projection, &Camera, &mut CameraLimits, &Views)>,
+    window: Single<&Window>, boards: Query<&Board>,
+) {
+    for (mut transform, mut projection, camera, mut limits, views) in cameras {
+        let Ok(board) = boards.get(views.0) else { continue };
+        let camera = (&mut *transform, &mut *projection, camera, &mut *limits);
+        fit_view(camera, &window, board, board.initial_view());
+    }
+}
+
+fn show_board(
+    cameras: Query<(&mut Transform, &mut Projection, &Camera, &mut CameraLimits, &Views)>,
+    window: Single<&Window>, boards: Query<&Board>,
+) {
+    for (mut transform, mut projection, camera, mut limits, views) in cameras {
+        let Ok(board) = boards.get(views.0) else { continue };
+        let camera = (&mut *transform, &mut *projection, camera, &mut *limits);
+        fit_view(camera, &window, board, board.bounds().inflate(0.25));
+    }
 }
 
 fn window_resized(
     mut resize_events: MessageReader<WindowResized>,
-    camera: Single<&mut Projection, With<MainCamera>>, mut limit: ResMut<CameraLimits>,
-    board: Res<Board>,
+    cameras: Query<(&mut Projection, &Camera, &mut CameraLimits)>, window: Single<&Window>,
 ) {
-    let Some(window) = resize_events.read().last() else { return };
-    let mut projection = camera.into_inner();
-    let orthographic = projection.as_orthographic_mut().unwrap();
-    let window_size = Vec2::new(window.width, window.height);
-    limit.calculate_scale_limits(board.size(), window_size);
-    limit.limit_scale(&mut orthographic.scale);
+    if resize_events.read().last().is_none() {
+        return;
+    }
+    for (mut projection, camera, mut limits) in cameras {
+        let orthographic = projection.as_orthographic_mut().unwrap();
+        limits.calculate_scale_limits(view_size(camera, &window));
+        limits.limit_scale(&mut orthographic.scale);
+    }
+}
+
+fn bounds_changed(
+    cameras: Query<(&mut Transform, &Projection, &Camera, &mut CameraLimits, &Views)>,
+    window: Single<&Window>, boards: Query<&Board, Changed<Board>>,
+) {
+    for (mut transform, projection, camera, mut limits, views) in cameras {
+        let Ok(board) = boards.get(views.0) else { continue };
+        if limits.bounds == board.bounds() {
+            continue;
+        }
+        let orthographic = projection.as_orthographic().unwrap();
+        let view_size = view_size(camera, &window);
+        limits.bounds = board.bounds();
+        limits.calculate_scale_limits(view_size);
+        limits.calculate_translation_limits(view_size, orthographic.scale);
+        limits.limit_translation(&mut transform.translation);
+    }
 }
 
 fn scale_changed(
-    camera: Single<(&mut Transform, &Projection), (Changed<Projection>, With<MainCamera>)>,
-    window: Single<&Window>, mut limit: ResMut<CameraLimits>, board: Res<Board>,
+    cameras: Query<(&mut Transform, &Projection, &Camera, &mut CameraLimits), Changed<Projection>>,
+    window: Single<&Window>,
 ) {
-    let (mut transform, projection) = camera.into_inner();
-    let orthographic = projection.as_orthographic().unwrap();
-    limit.calculate_translation_limits(board.size(), window.size(), orthographic.scale);
-    limit.limit_translation(&mut transform.translation);
+    for (mut transform, projection, camera, mut limits) in cameras {
+        let orthographic = projection.as_orthographic().unwrap();
+        limits.calculate_translation_limits(view_size(camera, &window), orthographic.scale);
+        limits.limit_translation(&mut transform.translation);
+    }
 }
 
-fn despawn(mut commands: Commands) { commands.remove_resource::<CameraLimits>(); }
+fn despawn(mut commands: Commands, camera: Single<(Entity, &mut Camera), With<MainCamera>>) {
+    let (entity, mut camera) = camera.into_inner();
+    camera.viewport = None;
+    commands.entity(entity).remove::<(Views, CameraLimits, RenderLayers)>();
+}
 
 pub fn camera(app: &mut App) {
     app.add_systems(Startup, spawn_main)
-        .add_systems(OnEnter(AppState::Playing), spawn)
-        .add_systems(OnExit(AppState::Playing), spawn)
-        .add_systems(Update, (window_resized, scale_changed).run_if(in_state(AppState::Playing)))
+        .add_systems(OnEnter(AppState::Playing), (arrange, spawn).chain())
+        .add_systems(OnExit(AppState::Playing), show_board)
+        .add_systems(
+            Update,
+            (
+                arrange.run_if(on_message::<WindowResized>),
+                window_resized,
+                bounds_changed,
+                scale_changed,
+            )
+                .chain()
+                .run_if(in_state(AppState::Playing)),
+        )
         .add_systems(OnEnter(AppState::Menu), despawn);
 }
@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader};
+use std::net::{Shutdown, TcpStream};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Mutex, PoisonError};
+use std::{env, thread};
+
+use bevy::prelude::*;
+
+use crate::AppState;
+use crate::net::{
+    ClientMessage, PROTOCOL_VERSION, ServerMessage, decode, grid, read_message, write_message,
+};
+use crate::plugins::{
+    Board, Chorded, Coordinates, Difficulty, Face, FlagPlaced, FlagRemoved, LeftClicked,
+    MainCamera, Mode, RegionRevealed, RightClicked, Size, Theme, ThemeColor, spawn_players,
+};
+
+pub const PLAYER_COLORS: [ThemeColor; 6] = [
+    ThemeColor::Accent,
+    ThemeColor::Easy,
+    ThemeColor::Medium,
+    ThemeColor::Hard,
+    ThemeColor::Extreme,
+    ThemeColor::Debug,
+];
+const CURSOR_RATE: f32 = 0.05;
+
+#[derive(Resource)]
+pub struct Connection {
+    stream: TcpStream,
+    messages: Mutex<Receiver<ServerMessage>>,
+    player: Option<u32>,
+    started: bool,
+}
+
+impl Connection {
+    pub fn open(address: &str) -> io::Result<Self> {
+        let mut stream = TcpStream::connect(address)?;
+        write_message(&mut stream, &ClientMessage::Hello { version: PROTOCOL_VERSION })?;
+        let reader = BufReader::new(stream.try_clone()?);
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in reader.lines().map_while(Result::ok) {
+                match read_message(&line) {
+                    Ok(message) => {
+                        if sender.send(message).is_err() {
+                            break;
+                        }
+                    },
+                    Err(e) => warn!("Failed to parse a server message: {e}"),
+                }
+            }
+        });
+        Ok(Self { stream, messages: Mutex::new(receiver), player: None, started: false })
+    }
+
+    fn send(&mut self, message: &ClientMessage) {
+        if let Err(e) = write_message(&mut self.stream, message) {
+            warn!("Failed to send to the server: {e}");
+        }
+    }
+
+    fn drain(&self) -> Vec<ServerMessage> {
+        self.messages.lock().unwrap_or_else(PoisonError::into_inner).try_iter().collect()
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) { self.stream.shutdown(Shutdown::Both).ok(); }
+}
+
+#[derive(Component)]
+pub struct Remote;
+
+#[derive(Component, Default)]
+pub struct Owners(pub HashMap<Coordinates, u32>);
+
+#[derive(Component)]
+struct PeerCursor(u32);
+
+#[derive(Component)]
+struct FlagMark;
+
+fn player_color(theme: &Theme, player: u32) -> Color {
+    theme.color(PLAYER_COLORS[player as usize % PLAYER_COLORS.len()])
+}
+
+fn import() -> Option<Connection> {
+    let address = env::args().skip_while(|arg| arg != "--connect").nth(1)?;
+    let connection = Connection::open(&address);
+    connection.inspect_err(|e| warn!("Failed to connect to {address}: {e}")).ok()
+}
+
+fn mirror(size: Size, difficulty: Difficulty, faces: &str) -> Board {
+    let mut board = Board::mirror(size, difficulty);
+    for (c, face) in grid(size).zip(faces.chars().filter_map(decode)) {
+        board.show(c, face);
+    }
+    board
+}
+
+fn leave(mut commands: Commands, connection: Res<Connection>) {
+    if connection.started {
+        commands.remove_resource::<Connection>();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn receive(
+    mut commands: Commands, mut connection: ResMut<Connection>,
+    mut boards: Query<(Entity, &mut Board, &mut Owners), With<Remote>>,
+    mut cursors: Query<(Entity, &PeerCursor, &mut Transform)>,
+    camera: Single<Entity, With<MainCamera>>, state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>, theme: Res<Theme>,
+) {
+    for message in connection.drain() {
+        match message {
+            ServerMessage::Welcome { player, .. } => connection.player = Some(player),
+            ServerMessage::Rejected(reason) => {
+                warn!("The server rejected the connection: {reason}");
+                commands.remove_resource::<Connection>();
+            },
+            ServerMessage::Board { size, difficulty, faces, owners } => {
+                let board = mirror(size, difficulty, &faces);
+                let owners = Owners(owners.into_iter().collect());
+                if let Ok((_, mut mirror, mut current)) = boards.single_mut() {
+                    *mirror = board;
+                    *current = owners;
+                    if matches!(**state, AppState::Won | AppState::Lost) {
+                        next_state.set(AppState::Playing);
+                    }
+                } else if **state == AppState::Menu && !connection.started {
+                    let boards = spawn_players(&mut commands, board, Mode::Solo, *camera, &[]);
+                    commands.entity(boards[0]).insert((Remote, owners));
+                    connection.started = true;
+                    next_state.set(AppState::Playing);
+                }
+            },
+            ServerMessage::Tiles { tiles, .. } => {
+                let Ok((entity, mut board, _)) = boards.single_mut() else { continue };
+                let mut revealed = Vec::new();
+                for (c, face) in tiles {
+                    board.show(c, face);
+                    if matches!(face, Face::Empty | Face::Neighbours(_) | Face::BombClicked) {
+                        revealed.push(c);
+                    }
+                }
+                if !revealed.is_empty() {
+                    commands.trigger(RegionRevealed { board: entity, tiles: revealed });
+                }
+            },
+            ServerMessage::Flagged { player, coordinates, placed } => {
+                let Ok((entity, mut board, mut owners)) = boards.single_mut() else { continue };
+                if placed {
+                    board.show(coordinates, Face::Flagged);
+                    owners.0.insert(coordinates, player);
+                    commands.trigger(FlagPlaced { board: entity, coordinates });
+                } else {
+                    board.show(coordinates, Face::Covered);
+                    owners.0.remove(&coordinates);
+                    commands.trigger(FlagRemoved { board: entity, coordinates });
+                }
+            },
+            ServerMessage::Cursor { player, .. } if connection.player == Some(player) => {},
+            ServerMessage::Cursor { player, x, y } => {
+                let translation = Vec3::new(x, y, 2.0);
+                if let Some((.., mut transform)) =
+                    cursors.iter_mut().find(|(_, c, _)| c.0 == player)
+                {
+                    transform.translation = translation;
+                } else if boards.single().is_ok() {
+                    commands.spawn((
+                        Sprite::from_color(player_color(&theme, player), Vec2::splat(0.3)),
+                        Transform::from_translation(translation),
+                        PeerCursor(player),
+                        DespawnOnEnter(AppState::Menu),
+                    ));
+                }
+            },
+            ServerMessage::Left { player } => {
+                for (entity, cursor, _) in &cursors {
+                    if cursor.0 == player {
+                        commands.entity(entity).despawn();
+                    }
+                }
+            },
+        }
+    }
+}
+
+fn send_cursor(
+    mut connection: ResMut<Connection>,
+    camera: Single<(&GlobalTransform, &Camera), With<MainCamera>>, window: Single<&Window>,
+    time: Res<Time>, mut last: Local<(f32, Vec2)>,
+) {
+    last.0 += time.delta_secs();
+    if last.0 < CURSOR_RATE {
+        return;
+    }
+    let (global, camera) = camera.into_inner();
+    let Some(world) =
+        window.cursor_position().and_then(|c| camera.viewport_to_world_2d(global, c).ok())
+    else {
+        return;
+    };
+    if world != last.1 {
+        *last = (0.0, world);
+        connection.send(&ClientMessage::Cursor { x: world.x, y: world.y });
+    }
+}
+
+fn draw_owners(
+    mut commands: Commands, owners: Single<&Owners, Changed<Owners>>,
+    marks: Query<Entity, With<FlagMark>>, theme: Res<Theme>,
+) {
+    for entity in marks {
+        commands.entity(entity).despawn();
+    }
+    for (&c, &player) in &owners.0 {
+        commands.spawn((
+            Sprite::from_color(player_color(&theme, player), Vec2::splat(0.25)),
+            Transform::from_translation((c.center() + Vec2::new(0.3, 0.3)).extend(1.5)),
+            FlagMark,
+            DespawnOnEnter(AppState::Menu),
+        ));
+    }
+}
+
+fn send_reveal(
+    clicked: On<LeftClicked>, remote: Query<(), With<Remote>>,
+    connection: Option<ResMut<Connection>>,
+) {
+    if remote.contains(clicked.board)
+        && let Some(mut connection) = connection
+    {
+        connection.send(&ClientMessage::Reveal(clicked.coordinates));
+    }
+}
+
+fn send_flag(
+    clicked: On<RightClicked>, remote: Query<(), With<Remote>>,
+    connection: Option<ResMut<Connection>>,
+) {
+    if remote.contains(clicked.board)
+        && let Some(mut connection) = connection
+    {
+        connection.send(&ClientMessage::Flag(clicked.coordinates));
+    }
+}
+
+fn send_chord(
+    chorded: On<Chorded>, remote: Query<(), With<Remote>>, connection: Option<ResMut<Connection>>,
+) {
+    if remote.contains(chorded.board)
+        && let Some(mut connection) = connection
+    {
+        connection.send(&ClientMessage::Chord(chorded.coordinates));
+    }
+}
+
+fn restart(mut connection: ResMut<Connection>, remote: Query<(), With<Remote>>) {
+    if !remote.is_empty() {
+        connection.send(&ClientMessage::Restart);
+    }
+}
+
+pub fn coop(app: &mut App) {
+    if let Some(connection) = import() {
+        app.insert_resource(connection);
+    }
+    let connected = resource_exists::<Connection>;
+    app.add_systems(
+        Update,
+        (leave.run_if(in_state(AppState::Menu)), receive.run_if(resource_exists::<Theme>))
+            .chain()
+            .run_if(connected),
+    )
+    .add_systems(
+        Update,
+        (
+            send_cursor.run_if(connected).run_if(in_state(AppState::Playing)),
+            draw_owners.run_if(resource_exists::<Theme>.and(not(in_state(AppState::Menu)))),
+        ),
+    )
+    .add_systems(
+        OnTransition { exited: AppState::Won, entered: AppState::Playing },
+        restart.run_if(connected),
+    )
+    .add_systems(
+        OnTransition { exited: AppState::Lost, entered: AppState::Playing },
+        restart.run_if(connected),
+    )
+    .add_observer(send_reveal)
+    .add_observer(send_flag)
+    .add_observer(send_chord);
+}
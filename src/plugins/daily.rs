@@ -0,0 +1,219 @@
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::AppState;
+use crate::plugins::main_menu::MenuButton;
+use crate::plugins::{
+    Board, Difficulty, Elapsed, Localized, MainCamera, Mode, Size, spawn_players,
+};
+use crate::utils::Persistent;
+
+pub const DAILY_SIZE: Size = Size::Big;
+pub const DAILY_DIFFICULTY: Difficulty = Difficulty::Medium;
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+pub struct Day(pub i64);
+
+impl Day {
+    #[must_use]
+    pub fn today() -> Self {
+        let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        let seconds = seconds as i64;
+        Self((seconds + local_offset(seconds)).div_euclid(86_400))
+    }
+
+    #[must_use]
+    pub fn seed(self) -> u64 {
+        let mut z = (self.0 as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    #[must_use]
+    pub fn board(self) -> Board { Board::new(DAILY_SIZE, DAILY_DIFFICULTY).with_seed(self.seed()) }
+}
+
+/// Seconds east of UTC for the local time zone at `seconds` since the epoch.
+///
+/// Only Unix targets read the system time zone; elsewhere the daily board rolls over at
+/// midnight UTC instead of local midnight.
+#[cfg(unix)]
+fn local_offset(seconds: i64) -> i64 {
+    let time = seconds as libc::time_t;
+    // SAFETY: `libc::tm` is a plain C struct of integers and a nullable pointer, so the
+    // all-zero bit pattern is a valid value.
+    let mut tm = unsafe { std::mem::zeroed::<libc::tm>() };
+    // SAFETY: both pointers come from live locals that outlive the call, and `localtime_r`
+    // is the reentrant variant that only writes into the `tm` we pass in.
+    if unsafe { libc::localtime_r(&raw const time, &raw mut tm) }.is_null() {
+        return 0;
+    }
+    tm.tm_gmtoff as i64
+}
+
+/// Falls back to UTC because no time zone lookup is available without an extra dependency.
+#[cfg(not(unix))]
+fn local_offset(_: i64) -> i64 { 0 }
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub won: bool,
+    pub seconds: u32,
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct DailyHistory {
+    pub results: BTreeMap<Day, DailyResult>,
+}
+
+impl Persistent for DailyHistory {
+    const FILE: &'static str = "daily.ron";
+}
+
+impl DailyHistory {
+    #[must_use]
+    pub fn played(&self, day: Day) -> bool { self.results.contains_key(&day) }
+
+    #[must_use]
+    pub fn streaks(&self, today: Day) -> (u32, u32) {
+        let (mut run, mut best, mut last) = (0, 0, None);
+        for (&day, result) in &self.results {
+            run = match (result.won, last) {
+                (false, _) => 0,
+                (true, Some(Day(previous))) if previous + 1 == day.0 => run + 1,
+                (true, _) => 1,
+            };
+            best = best.max(run);
+            last = Some(day);
+        }
+        let current = if last.is_some_and(|day| day.0 + 1 >= today.0) { run } else { 0 };
+        (current, best)
+    }
+
+    #[must_use]
+    pub fn label(&self, today: Day) -> Localized {
+        let (current, best) = self.streaks(today);
+        let id = if self.played(today) { "daily-practice" } else { "daily-challenge" };
+        Localized::new(id).with("streak", current).with("best", best)
+    }
+}
+
+#[derive(Component)]
+pub struct DailyButton;
+
+#[derive(Component, Copy, Clone, Debug)]
+pub struct Daily {
+    pub day: Day,
+    pub ranked: bool,
+}
+
+#[must_use]
+pub fn unranked(boards: Query<&Daily>) -> bool { boards.iter().all(|daily| !daily.ranked) }
+
+fn daily_click(
+    mut commands: Commands, mut next_state: ResMut<NextState<AppState>>,
+    interaction: Single<&Interaction, (Changed<Interaction>, With<MenuButton>, With<DailyButton>)>,
+    camera: Single<Entity, With<MainCamera>>,
+) {
+    if **interaction != Interaction::Pressed {
+        return;
+    }
+    let day = Day::today();
+    for entity in spawn_players(&mut commands, day.board(), Mode::Solo, *camera, &[]) {
+        commands.entity(entity).insert(Daily { day, ranked: false });
+    }
+    next_state.set(AppState::Playing);
+}
+
+fn start(boards: Query<&mut Daily>, mut history: ResMut<DailyHistory>) {
+    for mut daily in boards {
+        daily.ranked = !history.played(daily.day);
+        if daily.ranked {
+            history.results.insert(daily.day, DailyResult { won: false, seconds: 0 });
+            history.save();
+        }
+    }
+}
+
+fn record(
+    boards: Query<(&Board, &Daily)>, elapsed: Option<Res<Elapsed>>,
+    mut history: ResMut<DailyHistory>,
+) {
+    for (board, daily) in boards {
+        if daily.ranked && board.is_cleared() {
+            let seconds = elapsed.as_ref().map_or(0, |elapsed| elapsed.0 as u32);
+            history.results.insert(daily.day, DailyResult { won: true, seconds });
+            history.save();
+        }
+    }
+}
+
+pub fn daily(app: &mut App) {
+    app.insert_resource(DailyHistory::load())
+        .add_systems(Update, daily_click.run_if(in_state(AppState::Menu)))
+        .add_systems(OnEnter(AppState::Playing), start)
+        .add_systems(OnEnter(AppState::Won), record);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+    use crate::Pause;
+    use crate::plugins::{Action, board, layout};
+
+    #[test]
+    fn everyone_gets_the_same_board_on_the_same_day() {
+        let boards = [Day(20_380), Day(20_380), Day(20_381)].map(|day| {
+            let mut board = day.board();
+            board.start_seeded(day.seed());
+            board.export(false)
+        });
+        assert_eq!(boards[0], boards[1]);
+        assert_ne!(boards[0], boards[2]);
+    }
+
+    #[test]
+    fn streaks_count_consecutive_wins() {
+        let mut history = DailyHistory::default();
+        for (day, won) in [(1, true), (2, true), (3, true), (4, false), (6, true), (7, true)] {
+            history.results.insert(Day(day), DailyResult { won, seconds: 60 });
+        }
+        assert_eq!(history.streaks(Day(8)), (2, 3));
+        assert_eq!(history.streaks(Day(9)), (0, 3));
+        assert!(history.played(Day(7)) && !history.played(Day(5)));
+    }
+
+    #[test]
+    fn ranked_daily_ignores_hint_and_export() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin))
+            .init_state::<AppState>()
+            .add_sub_state::<Pause>()
+            .init_resource::<ButtonInput<Action>>()
+            .add_plugins((board, layout));
+        let day = Day(20_380);
+        let entity = app.world_mut().spawn((day.board(), Daily { day, ranked: true })).id();
+        app.world_mut().resource_mut::<NextState<AppState>>().set(AppState::Playing);
+        app.update();
+        let press = |app: &mut App, pressed: &[Action]| {
+            let mut actions = app.world_mut().resource_mut::<ButtonInput<Action>>();
+            actions.reset_all();
+            for &action in pressed {
+                actions.press(action);
+            }
+            app.update();
+            app.world().get::<Board>(entity).unwrap().uncovered()
+        };
+        assert_eq!(press(&mut app, &[Action::Hint, Action::Export]), 0);
+        assert!(!app.world_mut().run_system_cached(unranked).unwrap());
+        app.world_mut().get_mut::<Daily>(entity).unwrap().ranked = false;
+        assert!(app.world_mut().run_system_cached(unranked).unwrap());
+        assert!(press(&mut app, &[Action::Hint]) > 0);
+    }
+}
@@ -0,0 +1,126 @@
+use std::cmp::Ordering;
+
+use bevy::prelude::*;
+
+use crate::AppState;
+use crate::plugins::{Board, Clicks, FlagPlaced, LeftClicked, Owners, RegionRevealed};
+
+#[derive(Component, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Turns {
+    pub current: usize,
+    pub scores: [usize; 2],
+}
+
+impl Turns {
+    fn claim(&mut self) { self.scores[self.current] += 1; }
+
+    fn pass(&mut self) { self.current = 1 - self.current; }
+
+    #[must_use]
+    pub fn winner(&self, mines: usize) -> Option<usize> {
+        self.scores.iter().position(|&score| score * 2 > mines)
+    }
+
+    #[must_use]
+    pub fn leader(&self) -> Option<usize> {
+        let [first, second] = self.scores;
+        match first.cmp(&second) {
+            Ordering::Greater => Some(0),
+            Ordering::Less => Some(1),
+            Ordering::Equal => None,
+        }
+    }
+}
+
+fn take_turn(
+    clicked: On<LeftClicked>, mut commands: Commands,
+    mut boards: Query<(&mut Board, &mut Turns, &mut Owners, &mut Clicks)>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let (entity, coordinates) = (clicked.board, clicked.coordinates);
+    let Ok((mut board, mut turns, mut owners, mut clicks)) = boards.get_mut(entity) else { return };
+    if !board.contains(coordinates) {
+        return;
+    }
+    if board.claim(coordinates) {
+        owners.0.insert(coordinates, turns.current as u32);
+        turns.claim();
+        commands.trigger(FlagPlaced { board: entity, coordinates });
+        if turns.winner(board.mines).is_some() || board.flags() == board.mines {
+            next_state.set(AppState::Won);
+        }
+    } else {
+        let tiles = board.reveal([coordinates]);
+        if tiles.is_empty() {
+            return;
+        }
+        turns.pass();
+        commands.trigger(RegionRevealed { board: entity, tiles });
+    }
+    clicks.left += 1;
+}
+
+fn reset(boards: Query<(&mut Turns, &mut Owners)>) {
+    for (mut turns, mut owners) in boards {
+        *turns = Turns::default();
+        owners.0.clear();
+    }
+}
+
+pub fn flags(app: &mut App) {
+    app.add_observer(take_turn).add_systems(OnEnter(AppState::Playing), reset);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+    use crate::Pause;
+    use crate::plugins::{Action, Coordinates, Difficulty, Layout, Source, board};
+
+    #[test]
+    fn mines_keep_the_turn_and_half_wins() {
+        let mut turns = Turns::default();
+        turns.claim();
+        turns.claim();
+        turns.pass();
+        turns.claim();
+        assert_eq!(turns, Turns { current: 1, scores: [2, 1] });
+        assert_eq!(turns.leader(), Some(0));
+        assert_eq!(turns.winner(5), None);
+        turns.pass();
+        turns.claim();
+        assert_eq!(turns.winner(5), Some(0));
+        assert_eq!(Turns { current: 0, scores: [2, 2] }.leader(), None);
+    }
+
+    #[test]
+    fn clearing_the_safe_cells_leaves_the_end_to_the_scores() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin))
+            .init_state::<AppState>()
+            .add_sub_state::<Pause>()
+            .init_resource::<ButtonInput<Action>>()
+            .add_plugins((board, flags));
+        let layout = Layout::parse("*..\n").unwrap();
+        let board = Board::from_layout(layout, Difficulty::Easy);
+        let entity = app.world_mut().spawn((board, Turns::default(), Owners::default())).id();
+        app.world_mut().resource_mut::<NextState<AppState>>().set(AppState::Playing);
+        app.update();
+        let click = |app: &mut App, x| {
+            let coordinates = Coordinates::new(x, 0);
+            app.world_mut().trigger(LeftClicked {
+                board: entity,
+                coordinates,
+                source: Source::Mouse,
+            });
+            app.world_mut().flush();
+            app.update();
+            **app.world().resource::<State<AppState>>()
+        };
+        assert_eq!(click(&mut app, 2), AppState::Playing);
+        assert!(app.world().get::<Board>(entity).unwrap().is_cleared());
+        assert_eq!(click(&mut app, 0), AppState::Won);
+    }
+}
@@ -1,26 +1,158 @@
-use std::array;
+use std::fs;
 
+use bevy::asset::io::file::FileAssetReader;
 use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::AppState;
+use crate::plugins::{Action, Face, Preferences};
+use crate::utils::Persistent;
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Manifest {
+    name: Option<String>,
+    atlas: Option<String>,
+}
+
+enum Source {
+    Images(String),
+    Atlas(String, String),
+    Vector,
+}
+
+pub struct TexturePack {
+    pub name: String,
+    source: Source,
+}
+
+impl TexturePack {
+    fn file(face: Face) -> String {
+        match face {
+            Face::Covered => String::from("covered.png"),
+            Face::Pressed => String::from("pressed.png"),
+            Face::Flagged => String::from("flagged.png"),
+            Face::Empty => String::from("empty.png"),
+            Face::Neighbours(n) => format!("{n}.png"),
+            Face::Bomb => String::from("bomb.png"),
+            Face::BombClicked => String::from("bomb_clicked.png"),
+            Face::WrongFlag => String::from("wrong_flag.png"),
+        }
+    }
+
+    fn load(&self, asset_server: &AssetServer) -> Tiles {
+        let load = |dir: &str, file: &str| asset_server.load(format!("packs/{dir}/{file}"));
+        match &self.source {
+            Source::Images(dir) => {
+                Tiles::Images(Face::ALL.iter().map(|&face| load(dir, &Self::file(face))).collect())
+            },
+            Source::Atlas(dir, atlas) => Tiles::Images(vec![load(dir, atlas)]),
+            Source::Vector => Tiles::Vector,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct TexturePacks(Vec<TexturePack>);
+
+impl TexturePacks {
+    fn discover() -> Self {
+        let root = FileAssetReader::get_base_path().join("assets").join("packs");
+        let mut packs: Vec<_> = fs::read_dir(&root)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| {
+                let dir = entry.file_name().to_string_lossy().into_owned();
+                let path = entry.path().join("pack.ron");
+                let manifest = fs::read_to_string(&path).map_or_else(
+                    |_| Manifest::default(),
+                    |text| {
+                        ron::from_str(&text).unwrap_or_else(|e| {
+                            warn!("Failed to parse {}: {e}", path.display());
+                            Manifest::default()
+                        })
+                    },
+                );
+                let name = manifest.name.unwrap_or_else(|| dir.clone());
+                let source = match manifest.atlas {
+                    Some(atlas) => Source::Atlas(dir, atlas),
+                    None => Source::Images(dir),
+                };
+                TexturePack { name, source }
+            })
+            .collect();
+        if packs.is_empty() {
+            warn!("No texture packs found in {}", root.display());
+        }
+        packs.sort_by(|a, b| a.name.cmp(&b.name));
+        packs.push(TexturePack { name: String::from("Vector"), source: Source::Vector });
+        Self(packs)
+    }
+
+    #[must_use]
+    pub fn get(&self, name: &str) -> &TexturePack {
+        self.0.iter().find(|p| p.name == name).unwrap_or(&self.0[0])
+    }
+
+    #[must_use]
+    pub fn next(&self, name: &str) -> &TexturePack {
+        let index = self.0.iter().position(|p| p.name == name).map_or(0, |i| i + 1);
+        &self.0[index % self.0.len()]
+    }
+}
+
+pub enum Tiles {
+    Images(Vec<Handle<Image>>),
+    Vector,
+}
 
 #[derive(Resource)]
 pub struct GameAssets {
-    pub bomb: Handle<Image>,
-    pub bomb_clicked: Handle<Image>,
-    pub covered: Handle<Image>,
-    pub empty: Handle<Image>,
-    pub flagged: Handle<Image>,
-    pub neighbours: [Handle<Image>; 8],
-}
-
-fn spawn(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.insert_resource(GameAssets {
-        bomb: asset_server.load("bomb.png"),
-        bomb_clicked: asset_server.load("bomb_clicked.png"),
-        covered: asset_server.load("covered.png"),
-        empty: asset_server.load("empty.png"),
-        flagged: asset_server.load("flagged.png"),
-        neighbours: array::from_fn(|i| asset_server.load(format!("{}.png", i + 1))),
-    });
-}
-
-pub fn game_assets(app: &mut App) { app.add_systems(Startup, spawn); }
+    pub pack: String,
+    pub tiles: Tiles,
+}
+
+impl GameAssets {
+    #[must_use]
+    pub fn sprite(&self, face: Face, images: &Assets<Image>) -> Option<Sprite> {
+        let Tiles::Images(handles) = &self.tiles else { return None };
+        if let [atlas] = handles.as_slice() {
+            let size = images.get(atlas)?.size().as_vec2();
+            let height = size.y / Face::ALL.len() as f32;
+            let min = Vec2::new(0.0, height * face.index() as f32);
+            let rect = Rect::from_corners(min, min + Vec2::new(size.x, height));
+            return Some(Sprite { rect: Some(rect), ..Sprite::from_image(atlas.clone()) });
+        }
+        handles.get(face.index()).map(|handle| Sprite::from_image(handle.clone()))
+    }
+}
+
+fn select(
+    mut commands: Commands, asset_server: Res<AssetServer>, packs: Res<TexturePacks>,
+    preferences: Res<Preferences>, assets: Option<Res<GameAssets>>,
+) {
+    if assets.is_some_and(|a| a.pack == preferences.texture_pack) {
+        return;
+    }
+    let pack = packs.get(&preferences.texture_pack);
+    commands
+        .insert_resource(GameAssets { pack: pack.name.clone(), tiles: pack.load(&asset_server) });
+}
+
+fn cycle(
+    actions: Res<ButtonInput<Action>>, packs: Res<TexturePacks>,
+    mut preferences: ResMut<Preferences>,
+) {
+    if actions.just_pressed(Action::Tiles) {
+        preferences.texture_pack = packs.next(&preferences.texture_pack).name.clone();
+        preferences.save();
+    }
+}
+
+pub fn game_assets(app: &mut App) {
+    app.insert_resource(TexturePacks::discover())
+        .add_systems(PreUpdate, select.run_if(resource_changed::<Preferences>))
+        .add_systems(Update, cycle.run_if(in_state(AppState::Playing)));
+}
@@ -1,31 +1,51 @@
+use bevy::ecs::relationship::RelatedSpawnerCommands;
 use bevy::prelude::*;
 
-use crate::AppState;
 use crate::plugins::main_menu::Cycling;
-use crate::plugins::{Board, HideChildrenOnHover, TextValSize};
-use crate::utils::Nord;
+use crate::plugins::{
+    Action, Bindings, Board, Clicks, HideChildrenOnHover, Locale, PLAYER_COLORS, TextValSize,
+    ThemeColor, ThemedBackground, ThemedText, Turns, Views,
+};
+use crate::{AppState, Pause};
 
 #[derive(Component)]
-struct MineText;
+struct MineText(Entity);
 
 #[derive(Component)]
 struct TimeText;
 
+#[derive(Component)]
+struct ScoreText(Entity, usize);
+
 #[derive(Component)]
 struct Message;
 
+#[derive(Component)]
+struct MessageText(Entity);
+
+#[derive(Component)]
+struct StatsText(Entity);
+
 #[derive(Component)]
 struct HudRoot;
 
-#[derive(Resource)]
-pub struct MineCount(pub i32);
+#[derive(Component)]
+struct ProgressFill(Entity);
 
 #[derive(Resource)]
-struct Elapsed(f32);
+pub struct Elapsed(pub f32);
 
-fn spawn(mut commands: Commands, board: Res<Board>) {
-    commands.insert_resource(MineCount(board.mines as _));
+fn spawn(
+    mut commands: Commands, cameras: Query<(Entity, &Views)>, boards: Query<(&Board, Has<Turns>)>,
+) {
     commands.insert_resource(Elapsed(0.0));
+    for (camera, views) in cameras {
+        let Ok((board, turns)) = boards.get(views.0) else { continue };
+        spawn_root(&mut commands, camera, views.0, board, turns);
+    }
+}
+
+fn spawn_root(commands: &mut Commands, camera: Entity, entity: Entity, board: &Board, turns: bool) {
     commands
         .spawn((
             Node {
@@ -37,6 +57,7 @@ fn spawn(mut commands: Commands, board: Res<Board>) {
                 ..default()
             },
             HudRoot,
+            UiTargetCamera(camera),
         ))
         .with_children(|parent| {
             parent
@@ -66,119 +87,309 @@ fn spawn(mut commands: Commands, board: Res<Board>) {
                                 align_items: AlignItems::Center,
                                 ..default()
                             },
-                            BackgroundColor(Nord::NIGHT[0]),
+                            ThemedBackground(ThemeColor::Panel),
                             BorderRadius::all(Val::Percent(10.0)),
                         ))
                         .with_children(|parent| {
-                            parent.spawn((
-                                Text::new(""),
-                                TextValSize(Val::Percent(45.0)),
-                                MineText,
-                            ));
-                            parent.spawn((
-                                Text::new(""),
-                                TextValSize(Val::Percent(45.0)),
-                                TimeText,
-                            ));
+                            if turns {
+                                spawn_scores(parent, entity);
+                            } else {
+                                parent.spawn((
+                                    Text::new(""),
+                                    ThemedText(ThemeColor::Text),
+                                    TextValSize(Val::Percent(45.0)),
+                                    MineText(entity),
+                                ));
+                                parent.spawn((
+                                    Text::new(""),
+                                    ThemedText(ThemeColor::Text),
+                                    TextValSize(Val::Percent(45.0)),
+                                    TimeText,
+                                ));
+                            }
+                            if !board.is_endless() {
+                                spawn_progress(parent, entity);
+                            }
                             board.size.spawn(Val::Percent(45.0), false, parent);
                             board.difficulty.spawn(Val::Percent(45.0), false, parent);
                         });
                 });
+            spawn_message(parent, entity);
+        });
+}
+
+fn spawn_scores(parent: &mut RelatedSpawnerCommands<ChildOf>, board: Entity) {
+    for (player, color) in PLAYER_COLORS.into_iter().take(2).enumerate() {
+        parent.spawn((
+            Text::new(""),
+            ThemedText(color),
+            TextValSize(Val::Percent(45.0)),
+            ScoreText(board, player),
+        ));
+    }
+}
+
+fn spawn_progress(parent: &mut RelatedSpawnerCommands<ChildOf>, board: Entity) {
+    parent
+        .spawn((
+            Node { width: Val::Percent(12.0), height: Val::Percent(30.0), ..default() },
+            ThemedBackground(ThemeColor::Track),
+            BorderRadius::all(Val::Percent(50.0)),
+        ))
+        .with_child((
+            Node { width: Val::Percent(0.0), height: Val::Percent(100.0), ..default() },
+            ThemedBackground(ThemeColor::Positive),
+            BorderRadius::all(Val::Percent(50.0)),
+            ProgressFill(board),
+        ));
+}
+
+fn spawn_message(parent: &mut RelatedSpawnerCommands<ChildOf>, board: Entity) {
+    parent
+        .spawn((
+            Node {
+                width: Val::VMin(90.0),
+                height: Val::VMin(24.0),
+                padding: UiRect::new(
+                    Val::VMin(10.0),
+                    Val::VMin(10.0),
+                    Val::VMin(13.0),
+                    Val::VMin(1.0),
+                ),
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            Visibility::Hidden,
+            Message,
+            HideChildrenOnHover,
+        ))
+        .with_children(|parent| {
             parent
                 .spawn((
                     Node {
-                        width: Val::VMin(90.0),
-                        height: Val::VMin(20.0),
-                        padding: UiRect::new(
-                            Val::VMin(10.0),
-                            Val::VMin(10.0),
-                            Val::VMin(13.0),
-                            Val::VMin(1.0),
-                        ),
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        flex_direction: FlexDirection::Column,
+                        justify_content: JustifyContent::SpaceEvenly,
                         align_items: AlignItems::Center,
                         ..default()
                     },
-                    Visibility::Hidden,
-                    Message,
-                    HideChildrenOnHover,
+                    ThemedBackground(ThemeColor::Panel),
+                    BorderRadius::all(Val::Percent(10.0)),
                 ))
                 .with_children(|parent| {
-                    parent
-                        .spawn((
-                            Node {
-                                width: Val::Percent(100.0),
-                                height: Val::Percent(100.0),
-                                justify_content: JustifyContent::Center,
-                                align_items: AlignItems::Center,
-                                ..default()
-                            },
-                            BackgroundColor(Nord::NIGHT[0]),
-                            BorderRadius::all(Val::Percent(10.0)),
-                        ))
-                        .with_child((
-                            Text::new("M: Menu, R: Restart"),
-                            TextValSize(Val::Percent(45.0)),
-                        ));
+                    parent.spawn((
+                        Text::default(),
+                        ThemedText(ThemeColor::Text),
+                        TextValSize(Val::Percent(30.0)),
+                        MessageText(board),
+                    ));
+                    parent.spawn((
+                        Text::default(),
+                        ThemedText(ThemeColor::Text),
+                        TextValSize(Val::Percent(22.0)),
+                        StatsText(board),
+                    ));
                 });
         });
 }
 
-fn reset(
-    board: Res<Board>, mut count: ResMut<MineCount>, mut elapsed: ResMut<Elapsed>,
-    mut message: Single<&mut Visibility, With<Message>>,
-) {
-    count.0 = board.mines as _;
+fn reset(mut elapsed: ResMut<Elapsed>, messages: Query<&mut Visibility, With<Message>>) {
     elapsed.0 = 0.0;
-    **message = Visibility::Hidden;
+    for mut message in messages {
+        *message = Visibility::Hidden;
+    }
 }
 
-fn update_mines(count: Res<MineCount>, text: Single<&mut Text, With<MineText>>) {
-    text.into_inner().0 = format!("Mines: {}", count.0);
+fn update_mines(
+    boards: Query<&Board, Changed<Board>>, locale: Res<Locale>,
+    texts: Query<(&MineText, &mut Text)>,
+) {
+    for (mine_text, mut text) in texts {
+        let Ok(board) = boards.get(mine_text.0) else { continue };
+        let mines = if board.is_endless() {
+            locale.format("hud-score", [("score", board.uncovered().into())])
+        } else {
+            locale.format("hud-mines", [("count", board.remaining_mines().into())])
+        };
+        if text.0 != mines {
+            text.0 = mines;
+        }
+    }
+}
+
+fn update_scores(
+    boards: Query<&Turns, Changed<Turns>>, locale: Res<Locale>,
+    texts: Query<(&ScoreText, &mut Text)>,
+) {
+    for (score_text, mut text) in texts {
+        let Ok(turns) = boards.get(score_text.0) else { continue };
+        let player = score_text.1;
+        let key = if turns.current == player { "flags-turn" } else { "flags-score" };
+        text.0 = locale
+            .format(key, [("player", (player + 1).into()), ("score", turns.scores[player].into())]);
+    }
+}
+
+fn update_progress(
+    boards: Query<&Board, Changed<Board>>, fills: Query<(&ProgressFill, &mut Node)>,
+) {
+    for (fill, mut node) in fills {
+        let Ok(board) = boards.get(fill.0) else { continue };
+        let width = Val::Percent(board.progress() * 100.0);
+        if node.width != width {
+            node.width = width;
+        }
+    }
 }
 
 fn update_time(
-    mut text: Single<&mut Text, With<TimeText>>, time: Res<Time>, mut elapsed: ResMut<Elapsed>,
+    texts: Query<&mut Text, With<TimeText>>, time: Res<Time>, mut elapsed: ResMut<Elapsed>,
+    locale: Res<Locale>,
 ) {
     elapsed.0 += time.delta_secs();
-    let rounded = elapsed.0 as u32;
-    let h = rounded / 3600;
-    let m = (rounded % 3600) / 60;
-    let s = rounded % 60;
-    text.0 = match (h, m, s) {
-        (1.., ..) => format!("Time: {h}:{m:02}:{s:02}"),
-        (0, 1.., ..) => format!("Time: {m}:{s:02}"),
-        (0, 0, ..) => format!("Time: {s}"),
-    };
+    let time = locale.format("hud-time", [("time", locale.duration(elapsed.0 as u32).into())]);
+    for mut text in texts {
+        text.0.clone_from(&time);
+    }
 }
 
-fn force_zero(mut text: Single<&mut Text, With<MineText>>) { text.0 = String::from("Mines: 0"); }
+fn force_zero(texts: Query<(&MineText, &mut Text)>, boards: Query<&Board>, locale: Res<Locale>) {
+    for (mine_text, mut text) in texts {
+        if boards.get(mine_text.0).is_ok_and(Board::is_cleared) {
+            text.0 = locale.format("hud-mines", [("count", 0.into())]);
+        }
+    }
+}
 
-fn show_message(mut message: Single<&mut Visibility, With<Message>>) {
-    **message = Visibility::Visible;
+fn result_key(board: &Board, race: bool) -> &'static str {
+    match (race, board.is_cleared(), board.is_exploded()) {
+        (false, ..) => "hud-game-over",
+        (true, true, _) => "race-won",
+        (true, false, true) => "race-eliminated",
+        (true, false, false) => "race-lost",
+    }
 }
 
-fn despawn(mut commands: Commands, root: Single<Entity, With<HudRoot>>) {
-    commands.entity(*root).despawn();
-    commands.remove_resource::<MineCount>();
+fn show_message(
+    messages: Query<&mut Visibility, With<Message>>,
+    texts: Query<(&MessageText, &mut Text), Without<StatsText>>,
+    stats: Query<(&StatsText, &mut Text), Without<MessageText>>, bindings: Res<Bindings>,
+    boards: Query<(&Board, &Clicks, Option<&Turns>)>, locale: Res<Locale>,
+) {
+    for mut message in messages {
+        *message = Visibility::Visible;
+    }
+    let race = boards.iter().count() > 1;
+    for (message_text, mut text) in texts {
+        let Ok((board, _, turns)) = boards.get(message_text.0) else { continue };
+        let menu = bindings.display(Action::Menu, &locale);
+        let restart = bindings.display(Action::Restart, &locale);
+        text.0 = match turns {
+            Some(turns) => {
+                let (high, low) = (turns.scores.iter().max(), turns.scores.iter().min());
+                let key = if turns.leader().is_some() { "flags-won" } else { "flags-tie" };
+                locale.format(key, [
+                    ("player", (turns.leader().unwrap_or_default() + 1).into()),
+                    ("score", high.copied().unwrap_or_default().into()),
+                    ("other", low.copied().unwrap_or_default().into()),
+                    ("menu", menu.into()),
+                    ("restart", restart.into()),
+                ])
+            },
+            None => locale.format(result_key(board, race), [
+                ("menu", menu.into()),
+                ("restart", restart.into()),
+            ]),
+        };
+    }
+    for (stats_text, mut text) in stats {
+        let Ok((board, clicks, _)) = boards.get(stats_text.0) else { continue };
+        let progress = match board.safe_cells() {
+            Some(safe) => locale.format("hud-revealed", [
+                ("revealed", board.uncovered().into()),
+                ("safe", safe.into()),
+            ]),
+            None => locale.format("hud-score", [("score", board.uncovered().into())]),
+        };
+        text.0 = locale.format("hud-stats", [
+            ("left", clicks.left.into()),
+            ("right", clicks.right.into()),
+            ("chords", clicks.chords.into()),
+            ("progress", progress.into()),
+            ("flags", board.flags().into()),
+        ]);
+    }
+}
+
+fn show_pause(
+    messages: Query<&mut Visibility, With<Message>>,
+    texts: Query<&mut Text, (With<MessageText>, Without<StatsText>)>,
+    stats: Query<&mut Text, (With<StatsText>, Without<MessageText>)>, bindings: Res<Bindings>,
+    locale: Res<Locale>,
+) {
+    for mut message in messages {
+        *message = Visibility::Visible;
+    }
+    for mut text in texts {
+        text.0 = locale.format("hud-pause", [
+            ("pause", bindings.display(Action::Pause, &locale).into()),
+            ("menu", bindings.display(Action::Menu, &locale).into()),
+        ]);
+    }
+    for mut text in stats {
+        text.0 = locale.text("hud-paused");
+    }
+}
+
+fn hide_pause(messages: Query<&mut Visibility, With<Message>>) {
+    for mut message in messages {
+        *message = Visibility::Hidden;
+    }
+}
+
+fn despawn(mut commands: Commands, roots: Query<Entity, With<HudRoot>>) {
+    for root in roots {
+        commands.entity(root).despawn();
+    }
     commands.remove_resource::<Elapsed>();
 }
 
-fn wait_for_key(input: Res<ButtonInput<KeyCode>>, mut next_state: ResMut<NextState<AppState>>) {
-    if input.just_pressed(KeyCode::KeyM) {
+fn wait_for_key(actions: Res<ButtonInput<Action>>, mut next_state: ResMut<NextState<AppState>>) {
+    if actions.just_pressed(Action::Menu) {
         next_state.set(AppState::Menu);
-    } else if input.just_pressed(KeyCode::KeyR) {
+    } else if actions.just_pressed(Action::Restart) {
         next_state.set(AppState::Playing);
     }
 }
 
+fn toggle_pause(
+    actions: Res<ButtonInput<Action>>, pause: Res<State<Pause>>,
+    mut next_pause: ResMut<NextState<Pause>>, mut next_state: ResMut<NextState<AppState>>,
+) {
+    if actions.just_pressed(Action::Pause) {
+        next_pause.set(if **pause == Pause::Running { Pause::Paused } else { Pause::Running });
+    } else if **pause == Pause::Paused && actions.just_pressed(Action::Menu) {
+        next_state.set(AppState::Menu);
+    }
+}
+
 pub fn hud(app: &mut App) {
-    app.add_systems(OnExit(AppState::Menu), spawn)
+    app.add_systems(OnTransition { exited: AppState::Menu, entered: AppState::Playing }, spawn)
         .add_systems(OnEnter(AppState::Playing), reset)
         .add_systems(
             Update,
-            (update_mines.run_if(resource_exists_and_changed::<MineCount>), update_time)
+            (
+                update_mines,
+                update_progress,
+                update_time.run_if(in_state(Pause::Running)),
+                toggle_pause,
+            )
                 .run_if(in_state(AppState::Playing)),
         )
+        .add_systems(Update, update_scores.run_if(not(in_state(AppState::Menu))))
+        .add_systems(OnEnter(Pause::Paused), show_pause)
+        .add_systems(OnExit(Pause::Paused), hide_pause)
         .add_systems(OnExit(AppState::Playing), show_message)
         .add_systems(OnEnter(AppState::Won), force_zero)
         .add_systems(
@@ -0,0 +1,198 @@
+use bevy::camera::visibility::RenderLayers;
+use bevy::prelude::*;
+
+use crate::plugins::{
+    Action, Announcement, Board, CameraLimits, Controller, Coordinates, LeftClicked, Locale,
+    Replay, RightClicked, Source, Theme, ThemeColor, Views, describe,
+};
+use crate::utils::ProjectionExt;
+use crate::{AppState, Pause};
+
+const MOVES: [(Action, GamepadButton, IVec2); 4] = [
+    (Action::CursorUp, GamepadButton::DPadUp, IVec2::new(0, -1)),
+    (Action::CursorDown, GamepadButton::DPadDown, IVec2::new(0, 1)),
+    (Action::CursorLeft, GamepadButton::DPadLeft, IVec2::new(-1, 0)),
+    (Action::CursorRight, GamepadButton::DPadRight, IVec2::new(1, 0)),
+];
+const REPEAT_DELAY: f32 = 0.35;
+const REPEAT_RATE: f32 = 0.07;
+const STICK_THRESHOLD: f32 = 0.5;
+const MARGIN: f32 = 1.0;
+
+#[derive(Component, Default)]
+struct Cursor(Option<Coordinates>);
+
+#[derive(Component, Default)]
+struct Repeat {
+    step: Option<IVec2>,
+    delay: f32,
+}
+
+#[derive(Component)]
+struct CursorSprite(Entity);
+
+fn reset(mut commands: Commands, boards: Query<Entity, With<Board>>) {
+    for entity in boards {
+        commands.entity(entity).insert((Cursor::default(), Repeat::default()));
+    }
+}
+
+fn stick_step(stick: Vec2) -> Option<IVec2> {
+    if stick.abs().max_element() < STICK_THRESHOLD {
+        None
+    } else if stick.x.abs() > stick.y.abs() {
+        Some(IVec2::new(stick.x.signum() as i32, 0))
+    } else {
+        Some(IVec2::new(0, -stick.y.signum() as i32))
+    }
+}
+
+fn step(
+    controller: Controller, actions: &ButtonInput<Action>, gamepads: &Query<(Entity, &Gamepad)>,
+) -> Option<IVec2> {
+    let keyboard = MOVES
+        .iter()
+        .find(|&&(action, ..)| controller.keyboard() && actions.pressed(action))
+        .map(|&(.., offset)| offset);
+    keyboard.or_else(|| {
+        gamepads.iter().filter(|&(entity, _)| controller.gamepad(entity)).find_map(
+            |(_, gamepad)| {
+                let dpad = MOVES.iter().find(|&&(_, button, _)| gamepad.pressed(button));
+                dpad.map(|&(.., offset)| offset).or_else(|| stick_step(gamepad.left_stick()))
+            },
+        )
+    })
+}
+
+fn move_cursor(
+    mut commands: Commands, actions: Res<ButtonInput<Action>>, time: Res<Time>,
+    boards: Query<(Entity, &Board, &Controller, &mut Cursor, &mut Repeat)>,
+    gamepads: Query<(Entity, &Gamepad)>, cameras: Query<(&Transform, &Views)>, locale: Res<Locale>,
+) {
+    for (entity, board, &controller, mut cursor, mut repeat) in boards {
+        let step = step(controller, &actions, &gamepads);
+        if step == repeat.step {
+            repeat.delay -= time.delta_secs();
+            if repeat.delay > 0.0 {
+                continue;
+            }
+            repeat.delay += REPEAT_RATE;
+        } else {
+            *repeat = Repeat { step, delay: REPEAT_DELAY };
+        }
+        let Some(offset) = step else { continue };
+        let current = match cursor.0 {
+            Some(current) => {
+                let target = Coordinates::new(current.x + offset.x, current.y + offset.y);
+                board.get_from_world(target.center()).map_or(current, |_| target)
+            },
+            None => cameras
+                .iter()
+                .find(|(_, views)| views.0 == entity)
+                .and_then(|(camera, _)| board.get_from_world(camera.translation.truncate()))
+                .unwrap_or_default(),
+        };
+        cursor.0 = Some(current);
+        commands.trigger(Announcement(describe(board, current, &locale)));
+    }
+}
+
+fn act(
+    mut commands: Commands, actions: Res<ButtonInput<Action>>,
+    boards: Query<(Entity, &Controller, &Cursor)>, gamepads: Query<(Entity, &Gamepad)>,
+) {
+    for (board, &controller, cursor) in boards {
+        let Some(coordinates) = cursor.0 else { continue };
+        let pressed = |action, buttons: &[GamepadButton]| {
+            (controller.keyboard() && actions.just_pressed(action))
+                || gamepads.iter().any(|(entity, gamepad)| {
+                    controller.gamepad(entity) && gamepad.any_just_pressed(buttons.iter().copied())
+                })
+        };
+        if pressed(Action::CursorReveal, &[GamepadButton::South]) {
+            commands.trigger(LeftClicked { board, coordinates, source: Source::Keyboard });
+        }
+        if pressed(Action::CursorFlag, &[GamepadButton::East, GamepadButton::West]) {
+            commands.trigger(RightClicked { board, coordinates, source: Source::Keyboard });
+        }
+    }
+}
+
+fn draw(
+    mut commands: Commands, boards: Query<(Entity, &Cursor, &RenderLayers), Changed<Cursor>>,
+    mut sprites: Query<(&CursorSprite, &mut Transform, &mut Visibility)>, theme: Res<Theme>,
+) {
+    for (board, cursor, layers) in boards {
+        let sprite = sprites.iter_mut().find(|(sprite, ..)| sprite.0 == board);
+        let Some(coordinates) = cursor.0 else {
+            if let Some((_, _, mut visibility)) = sprite {
+                *visibility = Visibility::Hidden;
+            }
+            continue;
+        };
+        let translation = coordinates.center().extend(0.5);
+        if let Some((_, mut transform, mut visibility)) = sprite {
+            transform.translation = translation;
+            *visibility = Visibility::Inherited;
+        } else {
+            let color = theme.color(ThemeColor::Accent).with_alpha(0.45);
+            commands.spawn((
+                Sprite::from_color(color, Vec2::ONE),
+                Transform::from_translation(translation),
+                CursorSprite(board),
+                layers.clone(),
+                DespawnOnEnter(AppState::Menu),
+            ));
+        }
+    }
+}
+
+fn follow(
+    boards: Query<(Entity, &Cursor), Changed<Cursor>>,
+    cameras: Query<(&mut Transform, &Projection, &CameraLimits, &Views)>,
+) {
+    for (mut transform, projection, limits, views) in cameras {
+        let Ok((_, cursor)) = boards.get(views.0) else { continue };
+        let Some(coordinates) = cursor.0 else { continue };
+        let Some(orthographic) = projection.as_orthographic() else { continue };
+        let area = orthographic.area;
+        let center = transform.translation.truncate();
+        let min = center + area.min + MARGIN;
+        let max = center + area.max - MARGIN;
+        let target = coordinates.center();
+        let shift = target.min(min) - min + target.max(max) - max;
+        if shift == Vec2::ZERO {
+            continue;
+        }
+        transform.translation += shift.extend(0.0);
+        limits.limit_translation(&mut transform.translation);
+    }
+}
+
+pub fn keyboard(app: &mut App) {
+    app.add_systems(OnEnter(AppState::Playing), reset)
+        .add_systems(
+            Update,
+            (move_cursor, act)
+                .chain()
+                .run_if(in_state(Pause::Running))
+                .run_if(not(resource_exists::<Replay>)),
+        )
+        .add_systems(
+            Update,
+            (draw, follow).run_if(resource_exists::<Theme>).run_if(not(in_state(AppState::Menu))),
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stick_moves_along_the_dominant_axis() {
+        assert_eq!(stick_step(Vec2::new(0.2, 0.3)), None);
+        assert_eq!(stick_step(Vec2::new(0.9, 0.6)), Some(IVec2::new(1, 0)));
+        assert_eq!(stick_step(Vec2::new(-0.1, 0.8)), Some(IVec2::new(0, -1)));
+        assert_eq!(stick_step(Vec2::new(0.0, -0.7)), Some(IVec2::new(0, 1)));
+    }
+}
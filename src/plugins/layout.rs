@@ -0,0 +1,66 @@
+use std::{env, fs};
+
+use bevy::prelude::*;
+
+use crate::AppState;
+use crate::plugins::mouse::in_viewport;
+use crate::plugins::{Action, Views, unranked};
+use crate::rules::{Board, Layout};
+use crate::utils::config_path;
+
+const EXPORT_FILE: &str = "board.txt";
+
+fn import() -> Option<Layout> {
+    let path = env::args().skip_while(|arg| arg != "--board").nth(1)?;
+    let layout =
+        fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|t| Layout::parse(&t));
+    layout.inspect_err(|e| warn!("Failed to import {path}: {e}")).ok()
+}
+
+fn export(
+    actions: Res<ButtonInput<Action>>, boards: Query<&Board>, windows: Query<&Window>,
+    cameras: Query<(&Camera, &Views)>,
+) {
+    if !actions.just_pressed(Action::Export) {
+        return;
+    }
+    let hovered = windows
+        .single()
+        .ok()
+        .and_then(Window::cursor_position)
+        .and_then(|cursor| cameras.iter().find(|&(camera, _)| in_viewport(camera, cursor)));
+    let Some(board) =
+        hovered.and_then(|(_, views)| boards.get(views.0).ok()).or_else(|| boards.single().ok())
+    else {
+        warn!("Point at a board to choose which one to export");
+        return;
+    };
+    let Some(layout) = board.export(true) else {
+        warn!("Endless boards cannot be exported");
+        return;
+    };
+    let Some(path) = config_path(EXPORT_FILE) else { return };
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::write(&path, layout.to_string()));
+    match result {
+        Ok(()) => info!("Exported board to {}", path.display()),
+        Err(e) => warn!("Failed to export {}: {e}", path.display()),
+    }
+}
+
+pub fn layout(app: &mut App) {
+    if let Some(layout) = import() {
+        app.insert_resource(layout);
+    }
+    app.add_systems(
+        Update,
+        export.run_if(
+            in_state(AppState::Playing)
+                .or(in_state(AppState::Won))
+                .or(in_state(AppState::Lost))
+                .and(unranked),
+        ),
+    );
+}
@@ -0,0 +1,250 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy::ui::UiSystems;
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use unic_langid::LanguageIdentifier;
+
+use crate::plugins::Preferences;
+
+const BUILT_IN: [(&str, &str); 3] = [
+    ("en-US", include_str!("../../assets/locales/en-US.ftl")),
+    ("de", include_str!("../../assets/locales/de.ftl")),
+    ("pl", include_str!("../../assets/locales/pl.ftl")),
+];
+
+const FONT: &[u8] = include_bytes!("../../assets/fonts/FiraMono-Medium.ttf");
+
+type Bundle = FluentBundle<FluentResource>;
+
+fn format_number<M>(value: &FluentValue, _: &M) -> Option<String> {
+    let FluentValue::Number(number) = value else { return None };
+    let digits = number.options.minimum_integer_digits?;
+    Some(format!("{:0>digits$}", number.as_string()))
+}
+
+fn bundle(id: &str, source: &str) -> Bundle {
+    let language: LanguageIdentifier = id.parse().unwrap_or_default();
+    let resource = FluentResource::try_new(source.to_owned()).unwrap_or_else(|(resource, e)| {
+        warn!("Failed to parse locale {id}: {e:?}");
+        resource
+    });
+    let mut bundle = FluentBundle::new_concurrent(vec![language]);
+    bundle.set_use_isolating(false);
+    bundle.set_formatter(Some(format_number));
+    let _ = bundle.add_builtins();
+    if let Err(e) = bundle.add_resource(resource) {
+        warn!("Failed to load locale {id}: {e:?}");
+    }
+    bundle
+}
+
+pub struct Language {
+    pub id: &'static str,
+    pub name: String,
+    bundle: Arc<Bundle>,
+}
+
+#[derive(Resource)]
+pub struct Languages(Vec<Language>);
+
+impl Languages {
+    fn built_in() -> Self {
+        Self(
+            BUILT_IN
+                .iter()
+                .map(|&(id, source)| {
+                    let bundle = Arc::new(bundle(id, source));
+                    let name = format(&bundle, "language-name", &FluentArgs::new())
+                        .unwrap_or_else(|| id.to_owned());
+                    Language { id, name, bundle }
+                })
+                .collect(),
+        )
+    }
+
+    #[must_use]
+    pub fn get(&self, id: &str) -> &Language {
+        self.0.iter().find(|l| l.id == id).unwrap_or(&self.0[0])
+    }
+
+    #[must_use]
+    pub fn next(&self, id: &str) -> &Language {
+        let index = self.0.iter().position(|l| l.id == id).map_or(0, |i| i + 1);
+        &self.0[index % self.0.len()]
+    }
+}
+
+fn format(bundle: &Bundle, id: &str, args: &FluentArgs) -> Option<String> {
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, Some(args), &mut errors);
+    if !errors.is_empty() {
+        warn!("Failed to format {id}: {errors:?}");
+    }
+    Some(text.into_owned())
+}
+
+#[derive(Resource)]
+pub struct Locale {
+    pub language: &'static str,
+    pub name: String,
+    bundle: Arc<Bundle>,
+    fallback: Arc<Bundle>,
+}
+
+impl Locale {
+    fn new(language: &Language, fallback: &Language) -> Self {
+        Self {
+            language: language.id,
+            name: language.name.clone(),
+            bundle: language.bundle.clone(),
+            fallback: fallback.bundle.clone(),
+        }
+    }
+
+    #[must_use]
+    pub fn text(&self, id: &str) -> String { self.format(id, []) }
+
+    pub fn format<'a>(
+        &self, id: &str, args: impl IntoIterator<Item = (&'a str, FluentValue<'a>)>,
+    ) -> String {
+        let args = FluentArgs::from_iter(args);
+        format(&self.bundle, id, &args)
+            .or_else(|| format(&self.fallback, id, &args))
+            .unwrap_or_else(|| {
+                warn!("Missing message {id}");
+                id.to_owned()
+            })
+    }
+
+    #[must_use]
+    pub fn duration(&self, seconds: u32) -> String {
+        self.format("time", [
+            ("hours", (seconds / 3600).into()),
+            ("minutes", (seconds / 60 % 60).into()),
+            ("seconds", (seconds % 60).into()),
+        ])
+    }
+}
+
+#[derive(Component, Clone, PartialEq)]
+pub struct Localized {
+    id: &'static str,
+    args: Vec<(&'static str, f64)>,
+}
+
+impl Localized {
+    #[must_use]
+    pub fn new(id: &'static str) -> Self { Self { id, args: Vec::new() } }
+
+    #[must_use]
+    pub fn with(mut self, name: &'static str, value: impl Into<f64>) -> Self {
+        self.args.push((name, value.into()));
+        self
+    }
+}
+
+fn install_font(mut fonts: ResMut<Assets<Font>>) {
+    match Font::try_from_bytes(FONT.to_vec()) {
+        Ok(font) => fonts.insert(AssetId::default(), font).unwrap(),
+        Err(e) => warn!("Failed to load font: {e}"),
+    }
+}
+
+fn select(
+    mut commands: Commands, languages: Res<Languages>, preferences: Res<Preferences>,
+    locale: Option<Res<Locale>>,
+) {
+    let language = languages.get(&preferences.language);
+    if locale.is_some_and(|l| l.language == language.id) {
+        return;
+    }
+    commands.insert_resource(Locale::new(language, &languages.0[0]));
+}
+
+fn translate(
+    locale: Res<Locale>, texts: Query<(Ref<Localized>, Option<&mut Text>, Option<&mut TextSpan>)>,
+) {
+    for (localized, text, span) in texts {
+        if !locale.is_changed() && !localized.is_changed() {
+            continue;
+        }
+        let args = localized.args.iter().map(|&(name, value)| (name, value.into()));
+        let value = locale.format(localized.id, args);
+        if let Some(mut text) = text {
+            text.0 = value;
+        } else if let Some(mut span) = span {
+            span.0 = value;
+        }
+    }
+}
+
+pub fn localization(app: &mut App) {
+    app.insert_resource(Languages::built_in())
+        .add_systems(Startup, install_font)
+        .add_systems(PreUpdate, select.run_if(resource_changed::<Preferences>))
+        .add_systems(
+            PostUpdate,
+            translate.run_if(resource_exists::<Locale>).before(UiSystems::Prepare),
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn messages(source: &str) -> HashSet<String> {
+        source
+            .lines()
+            .filter_map(|line| line.split_once(" ="))
+            .filter(|(id, _)| !id.starts_with(' '))
+            .map(|(id, _)| id.to_owned())
+            .collect()
+    }
+
+    fn locale(languages: &Languages, id: &str) -> Locale {
+        Locale::new(languages.get(id), &languages.0[0])
+    }
+
+    #[test]
+    fn built_in_languages_define_every_message() {
+        let english = messages(BUILT_IN[0].1);
+        for (id, source) in BUILT_IN {
+            assert_eq!(messages(source), english, "{id}");
+        }
+        let languages = Languages::built_in();
+        assert_eq!(languages.next("pl").id, "en-US");
+        assert_eq!(languages.get("xx").name, "English");
+    }
+
+    #[test]
+    fn plurals_and_timer() {
+        let languages = Languages::built_in();
+        let polish = locale(&languages, "pl");
+        let flags = |n: u32| {
+            let args = [
+                ("left", 0.into()),
+                ("right", 0.into()),
+                ("chords", 1.into()),
+                ("progress", "".into()),
+                ("flags", n.into()),
+            ];
+            polish.format("hud-stats", args).rsplit(' ').next().unwrap().to_owned()
+        };
+        assert_eq!(flags(1), "flaga");
+        assert_eq!(flags(3), "flagi");
+        assert_eq!(flags(5), "flag");
+        assert_eq!(flags(22), "flagi");
+        let english = locale(&languages, "en-US");
+        let time =
+            |seconds| english.format("hud-time", [("time", english.duration(seconds).into())]);
+        assert_eq!(time(7), "Time: 7");
+        assert_eq!(time(187), "Time: 3:07");
+        assert_eq!(time(7387), "Time: 2:03:07");
+        assert_eq!(english.text("missing-message"), "missing-message");
+    }
+}
@@ -1,28 +1,32 @@
-use std::fmt;
-use std::fmt::{Display, Formatter};
-
 use bevy::ecs::component::Mutable;
 use bevy::ecs::relationship::RelatedSpawnerCommands;
 use bevy::prelude::*;
 
 use crate::AppState;
-use crate::plugins::{Board, TextValSize};
-use crate::utils::Nord;
+use crate::plugins::{
+    Board, DailyButton, DailyHistory, Day, Difficulty, Layout, Localized, MainCamera, Mode, Size,
+    TextValSize, ThemeColor, ThemedBackground, ThemedBorder, ThemedText, spawn_players,
+};
 
 #[derive(Component, Clone)]
-struct MenuButton;
+pub struct MenuButton;
 
-pub trait Cycling: Display + Copy + Component<Mutability = Mutable> {
+pub trait Cycling: Copy + Component<Mutability = Mutable> {
     fn next(self) -> Self;
-    fn color(self) -> Color;
-    fn label(self) -> String;
+    fn color(self) -> ThemeColor;
+    fn label(self) -> &'static str;
+    fn value(self) -> Localized;
 
     fn spawn(
         self, text_size: Val, include_target: bool, parent: &mut RelatedSpawnerCommands<ChildOf>,
     ) {
         parent.spawn((Text::default(), TextValSize(text_size))).with_children(|parent| {
-            parent.spawn((TextSpan(self.label()), TextColor(Nord::SNOW[2])));
-            let base = (TextSpan(self.to_string()), TextColor(self.color()));
+            parent.spawn((
+                TextSpan::default(),
+                Localized::new(self.label()),
+                ThemedText(ThemeColor::Text),
+            ));
+            let base = (TextSpan::default(), self.value(), ThemedText(self.color()));
             if include_target {
                 parent.spawn((base, TargetText));
             } else {
@@ -33,26 +37,7 @@ pub trait Cycling: Display + Copy + Component<Mutability = Mutable> {
 }
 
 #[derive(Component)]
-struct TargetText;
-
-#[derive(Component, Copy, Clone)]
-pub enum Size {
-    Small,
-    Medium,
-    Big,
-    Huge,
-}
-
-impl Size {
-    pub fn dimensions(self) -> (usize, usize) {
-        match self {
-            Size::Small => (16, 9),
-            Size::Medium => (32, 18),
-            Size::Big => (48, 27),
-            Size::Huge => (64, 36),
-        }
-    }
-}
+pub struct TargetText;
 
 impl Cycling for Size {
     fn next(self) -> Self {
@@ -60,56 +45,42 @@ impl Cycling for Size {
             Size::Small => Size::Medium,
             Size::Medium => Size::Big,
             Size::Big => Size::Huge,
-            Size::Huge => Size::Small,
+            Size::Huge => Size::Giant,
+            Size::Giant => Size::Colossal,
+            Size::Colossal => Size::Endless,
+            Size::Endless | Size::Custom => Size::Small,
         }
     }
 
-    fn color(self) -> Color {
+    fn color(self) -> ThemeColor {
         match self {
-            Size::Small => Nord::GREEN,
-            Size::Medium => Nord::YELLOW,
-            Size::Big => Nord::ORANGE,
-            Size::Huge => Nord::RED,
+            Size::Small => ThemeColor::Easy,
+            Size::Medium => ThemeColor::Medium,
+            Size::Big => ThemeColor::Hard,
+            Size::Huge => ThemeColor::Extreme,
+            Size::Giant => ThemeColor::Giant,
+            Size::Colossal => ThemeColor::Colossal,
+            Size::Endless => ThemeColor::Accent,
+            Size::Custom => ThemeColor::Text,
         }
     }
 
-    fn label(self) -> String { String::from("Size: ") }
-}
+    fn label(self) -> &'static str { "size" }
 
-impl Display for Size {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        f.write_str(match self {
-            Size::Small => "Small",
-            Size::Medium => "Medium",
-            Size::Big => "Big",
-            Size::Huge => "Huge",
+    fn value(self) -> Localized {
+        Localized::new(match self {
+            Size::Small => "size-small",
+            Size::Medium => "size-medium",
+            Size::Big => "size-big",
+            Size::Huge => "size-huge",
+            Size::Giant => "size-giant",
+            Size::Colossal => "size-colossal",
+            Size::Endless => "size-endless",
+            Size::Custom => "size-custom",
         })
     }
 }
 
-#[derive(Component, Copy, Clone, PartialEq, Eq)]
-pub enum Difficulty {
-    Easy,
-    Medium,
-    Hard,
-    Extreme,
-    Debug,
-}
-
-impl Difficulty {
-    pub fn mine_count(self, cells: usize) -> usize {
-        match self {
-            Difficulty::Easy => cells / 10,
-            Difficulty::Medium => cells * 3 / 20,
-            Difficulty::Hard => cells / 5,
-            Difficulty::Extreme => cells / 4,
-            Difficulty::Debug => 36,
-        }
-    }
-
-    pub fn is_debug(self) -> bool { self == Self::Debug }
-}
-
 impl Cycling for Difficulty {
     fn next(self) -> Self {
         match self {
@@ -121,27 +92,25 @@ impl Cycling for Difficulty {
         }
     }
 
-    fn color(self) -> Color {
+    fn color(self) -> ThemeColor {
         match self {
-            Difficulty::Easy => Nord::GREEN,
-            Difficulty::Medium => Nord::YELLOW,
-            Difficulty::Hard => Nord::ORANGE,
-            Difficulty::Extreme => Nord::RED,
-            Difficulty::Debug => Nord::PURPLE,
+            Difficulty::Easy => ThemeColor::Easy,
+            Difficulty::Medium => ThemeColor::Medium,
+            Difficulty::Hard => ThemeColor::Hard,
+            Difficulty::Extreme => ThemeColor::Extreme,
+            Difficulty::Debug => ThemeColor::Debug,
         }
     }
 
-    fn label(self) -> String { String::from("Difficulty: ") }
-}
+    fn label(self) -> &'static str { "difficulty" }
 
-impl Display for Difficulty {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        f.write_str(match self {
-            Difficulty::Easy => "Easy",
-            Difficulty::Medium => "Medium",
-            Difficulty::Hard => "Hard",
-            Difficulty::Extreme => "Extreme",
-            Difficulty::Debug => "Debug",
+    fn value(self) -> Localized {
+        Localized::new(match self {
+            Difficulty::Easy => "difficulty-easy",
+            Difficulty::Medium => "difficulty-medium",
+            Difficulty::Hard => "difficulty-hard",
+            Difficulty::Extreme => "difficulty-extreme",
+            Difficulty::Debug => "difficulty-debug",
         })
     }
 }
@@ -149,36 +118,42 @@ impl Display for Difficulty {
 #[derive(Component)]
 struct Begin;
 
+#[derive(Component)]
+struct OpenSettings;
+
 #[derive(Component)]
 struct MainMenuRoot;
 
-fn button_base<M: Bundle>(marker: M) -> impl Bundle {
+pub fn button_base<M: Bundle>(height: Val, marker: M) -> impl Bundle {
     (
         Button,
         Node {
             width: Val::Percent(80.0),
-            height: Val::Percent(14.0),
+            height,
             justify_content: JustifyContent::Center,
             align_items: AlignItems::Center,
             border: UiRect::all(Val::Percent(0.5)),
             column_gap: Val::Percent(5.0),
             ..default()
         },
-        BackgroundColor(Nord::NIGHT[0]),
-        BorderColor::all(Nord::FROST[3]),
+        ThemedBackground(ThemeColor::Panel),
+        ThemedBorder(ThemeColor::Border),
         BorderRadius::all(Val::Percent(5.0)),
         MenuButton,
         marker,
     )
 }
 
-fn cycling_button<C: Cycling>(parent: &mut RelatedSpawnerCommands<ChildOf>, cycling: C) {
+pub fn cycling_button<C: Cycling>(
+    parent: &mut RelatedSpawnerCommands<ChildOf>, height: Val, cycling: C,
+) {
     parent
-        .spawn(button_base(cycling))
+        .spawn(button_base(height, cycling))
         .with_children(|parent| cycling.spawn(Val::Percent(45.0), true, parent));
 }
 
-fn spawn(mut commands: Commands) {
+fn spawn(mut commands: Commands, history: Res<DailyHistory>, layout: Option<Res<Layout>>) {
+    let size = if layout.is_some() { Size::Custom } else { Size::Small };
     commands
         .spawn((
             Node {
@@ -188,7 +163,7 @@ fn spawn(mut commands: Commands) {
                 justify_content: JustifyContent::Center,
                 ..default()
             },
-            BackgroundColor(Nord::NIGHT[0]),
+            ThemedBackground(ThemeColor::Background),
             MainMenuRoot,
         ))
         .with_children(|parent| {
@@ -204,73 +179,118 @@ fn spawn(mut commands: Commands) {
                 })
                 .with_children(|parent| {
                     parent.spawn((
-                        Text::new("Minesweeper"),
-                        TextColor(Nord::SNOW[2]),
-                        TextValSize(Val::Percent(12.0)),
+                        Text::default(),
+                        Localized::new("title"),
+                        ThemedText(ThemeColor::Text),
+                        TextValSize(Val::Percent(10.0)),
                     ));
-                    cycling_button(parent, Size::Small);
-                    cycling_button(parent, Difficulty::Easy);
-                    parent.spawn(button_base(Begin)).with_child((
-                        Text::new("Begin"),
-                        TextColor(Nord::SNOW[2]),
+                    cycling_button(parent, Val::Percent(12.0), size);
+                    cycling_button(parent, Val::Percent(12.0), Difficulty::Easy);
+                    cycling_button(parent, Val::Percent(12.0), Mode::Solo);
+                    parent.spawn(button_base(Val::Percent(12.0), Begin)).with_child((
+                        Text::default(),
+                        Localized::new("begin"),
+                        ThemedText(ThemeColor::Text),
+                        TextValSize(Val::Percent(45.0)),
+                    ));
+                    parent.spawn(button_base(Val::Percent(12.0), DailyButton)).with_child((
+                        Text::default(),
+                        history.label(Day::today()),
+                        ThemedText(ThemeColor::Text),
+                        TextValSize(Val::Percent(45.0)),
+                    ));
+                    parent.spawn(button_base(Val::Percent(12.0), OpenSettings)).with_child((
+                        Text::default(),
+                        Localized::new("settings"),
+                        ThemedText(ThemeColor::Text),
                         TextValSize(Val::Percent(45.0)),
                     ));
                 });
         });
 }
 
-fn buttons_hover(
+pub fn buttons_hover(
     mut interactions: Query<
-        (&Interaction, &mut BackgroundColor, &mut BorderColor),
+        (&Interaction, &mut ThemedBackground, &mut ThemedBorder),
         (Changed<Interaction>, With<MenuButton>),
     >,
 ) {
     for (interaction, mut background, mut border) in &mut interactions {
         match interaction {
             Interaction::Hovered => {
-                *background = BackgroundColor(Nord::NIGHT[1]);
-                *border = BorderColor::all(Nord::FROST[1]);
+                *background = ThemedBackground(ThemeColor::Hover);
+                *border = ThemedBorder(ThemeColor::HoverBorder);
             },
             Interaction::None => {
-                *background = BackgroundColor(Nord::NIGHT[0]);
-                *border = BorderColor::all(Nord::FROST[3]);
+                *background = ThemedBackground(ThemeColor::Panel);
+                *border = ThemedBorder(ThemeColor::Border);
             },
             Interaction::Pressed => {},
         }
     }
 }
 
-fn cycling_click<C: Cycling>(
-    interaction: Single<
-        (&Interaction, &mut C, &Children),
-        (Changed<Interaction>, With<MenuButton>),
-    >,
+pub fn cycling_click<C: Cycling>(
+    interaction: Single<(Ref<Interaction>, &mut C, &Children), With<MenuButton>>,
     text: Query<&Children, With<Text>>,
-    mut span: Query<(&mut TextSpan, &mut TextColor), With<TargetText>>,
+    mut span: Query<(&mut Localized, &mut ThemedText), With<TargetText>>,
 ) {
-    let (Interaction::Pressed, mut cycling, children) = interaction.into_inner() else {
+    let (interaction, mut cycling, children) = interaction.into_inner();
+    if interaction.is_changed() && *interaction == Interaction::Pressed {
+        *cycling = cycling.next();
+    }
+    if !cycling.is_changed() {
         return;
-    };
-    *cycling = cycling.next();
+    }
     for &e in children.into_iter().flat_map(|&e| text.get(e)).flatten() {
-        let Ok((mut text, mut color)) = span.get_mut(e) else { continue };
-        text.0 = cycling.to_string();
-        color.0 = cycling.color();
+        let Ok((mut localized, mut color)) = span.get_mut(e) else { continue };
+        *localized = cycling.value();
+        *color = ThemedText(cycling.color());
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn begin_click(
     mut commands: Commands, mut next_state: ResMut<NextState<AppState>>,
     interaction: Single<&Interaction, (Changed<Interaction>, With<MenuButton>, With<Begin>)>,
     size: Single<&Size, With<MenuButton>>, difficulty: Single<&Difficulty, With<MenuButton>>,
+    mode: Single<&Mode, With<MenuButton>>, layout: Option<Res<Layout>>,
+    camera: Single<Entity, With<MainCamera>>, gamepads: Query<Entity, With<Gamepad>>,
 ) {
     if **interaction != Interaction::Pressed {
         return;
     }
-    commands.insert_resource(Board::new(**size, **difficulty));
+    let board = match (**size, layout) {
+        (Size::Custom, Some(layout)) => Board::from_layout(layout.clone(), **difficulty),
+        (size, _) => Board::new(size, **difficulty),
+    };
+    let gamepads: Vec<_> = gamepads.iter().collect();
+    spawn_players(&mut commands, board, **mode, *camera, &gamepads);
     next_state.set(AppState::Playing);
 }
 
+fn skip_endless(mode: Single<&Mode, With<MenuButton>>, size: Single<&mut Size, With<MenuButton>>) {
+    let mut size = size.into_inner();
+    if **mode == Mode::Flags && *size == Size::Endless {
+        *size = size.next();
+    }
+}
+
+fn clear_import(mut commands: Commands, size: Single<&Size, (Changed<Size>, With<MenuButton>)>) {
+    if **size != Size::Custom {
+        commands.remove_resource::<Layout>();
+    }
+}
+
+fn settings_click(
+    mut next_state: ResMut<NextState<AppState>>,
+    interaction: Single<&Interaction, (Changed<Interaction>, With<MenuButton>, With<OpenSettings>)>,
+) {
+    if **interaction == Interaction::Pressed {
+        next_state.set(AppState::Settings);
+    }
+}
+
 fn despawn(mut commands: Commands, root: Single<Entity, With<MainMenuRoot>>) {
     commands.entity(root.into_inner()).despawn();
 }
@@ -279,7 +299,16 @@ pub fn main_menu(app: &mut App) {
     app.add_systems(OnEnter(AppState::Menu), spawn)
         .add_systems(
             Update,
-            (buttons_hover, cycling_click::<Size>, cycling_click::<Difficulty>, begin_click)
+            (
+                buttons_hover,
+                cycling_click::<Size>,
+                cycling_click::<Difficulty>,
+                cycling_click::<Mode>,
+                skip_endless.after(cycling_click::<Size>).after(cycling_click::<Mode>),
+                clear_import.run_if(resource_exists::<Layout>),
+                begin_click,
+                settings_click,
+            )
                 .run_if(in_state(AppState::Menu)),
         )
         .add_systems(OnExit(AppState::Menu), despawn);
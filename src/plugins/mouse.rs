@@ -1,36 +1,45 @@
 use bevy::input::mouse::AccumulatedMouseScroll;
 use bevy::prelude::*;
 
-use crate::AppState;
-use crate::plugins::{Board, CameraLimits, MainCamera};
+use crate::plugins::{
+    Action, ActionEvent, ActionEvents, Board, CameraLimits, Controller, Coordinates, Replay,
+    Source, Views,
+};
 use crate::utils::ProjectionExt;
+use crate::{AppState, Pause};
 
 #[derive(Resource, Default)]
-struct MouseState {
+pub struct MouseState {
+    pub camera: Option<Entity>,
     dragging: bool,
+    chorded: bool,
+    chord_held: bool,
     cursor_start: Vec2,
-    world_start: Vec2,
+    pub world_start: Vec2,
     translation_start: Vec3,
     global_start: GlobalTransform,
 }
 
 impl MouseState {
-    fn set(
-        &mut self, cursor: Vec2, transform: Transform, global: &GlobalTransform, camera: &Camera,
+    pub fn set(
+        &mut self, entity: Entity, cursor: Vec2, transform: Transform, global: &GlobalTransform,
+        camera: &Camera,
     ) {
+        self.camera = Some(entity);
         self.dragging = false;
+        self.chorded = false;
         self.cursor_start = cursor;
         self.world_start = camera.viewport_to_world_2d(global, cursor).unwrap();
         self.translation_start = transform.translation;
         self.global_start = *global;
     }
 
-    fn check_dragging(&mut self, cursor: Vec2) -> bool {
+    pub fn check_dragging(&mut self, cursor: Vec2) -> bool {
         self.dragging = self.dragging || (cursor - self.cursor_start).length_squared() >= 25.0;
         self.dragging
     }
 
-    fn get_translation(&self, cursor: Vec2, camera: &Camera) -> Vec3 {
+    pub fn get_translation(&self, cursor: Vec2, camera: &Camera) -> Vec3 {
         let end = camera.viewport_to_world_2d(&self.global_start, cursor).unwrap();
         self.translation_start - (end - self.world_start).extend(0.0)
     }
@@ -38,55 +47,158 @@ impl MouseState {
 
 #[derive(EntityEvent)]
 pub struct LeftClicked {
-    entity: Entity,
+    #[event_target]
+    pub board: Entity,
+    pub coordinates: Coordinates,
+    pub source: Source,
 }
 
 #[derive(EntityEvent)]
 pub struct RightClicked {
-    entity: Entity,
+    #[event_target]
+    pub board: Entity,
+    pub coordinates: Coordinates,
+    pub source: Source,
 }
 
-fn spawn(mut commands: Commands) { commands.insert_resource(MouseState::default()); }
+#[derive(EntityEvent)]
+pub struct Chorded {
+    #[event_target]
+    pub board: Entity,
+    pub coordinates: Coordinates,
+    pub source: Source,
+}
+
+#[derive(Component, Default)]
+pub struct PressPreview {
+    pub coordinates: Option<Coordinates>,
+    pub chord: bool,
+}
+
+pub fn in_viewport(camera: &Camera, position: Vec2) -> bool {
+    camera.logical_viewport_rect().is_some_and(|rect| rect.contains(position))
+}
+
+fn spawn(mut commands: Commands, boards: Query<Entity, With<Board>>) {
+    commands.insert_resource(MouseState::default());
+    for entity in boards {
+        commands.entity(entity).insert(PressPreview::default());
+    }
+}
 
 fn click_event(
     mut commands: Commands,
-    camera: Single<(&mut Transform, &GlobalTransform, &Camera), With<Camera2d>>,
-    window: Single<&mut Window>, mut state: ResMut<MouseState>,
-    button: Res<ButtonInput<MouseButton>>, board: Res<Board>, limits: Res<CameraLimits>,
+    mut cameras: Query<(Entity, &mut Transform, &GlobalTransform, &Camera, &Views, &CameraLimits)>,
+    window: Single<&Window>, events: Res<ActionEvents>, mut state: ResMut<MouseState>,
+    actions: Res<ButtonInput<Action>>, boards: Query<(&Board, &Controller)>,
 ) {
-    let Some(cursor) = window.cursor_position() else { return };
-    let (mut transform, global, camera) = camera.into_inner();
-    let cursor_world = camera.viewport_to_world_2d(global, cursor).unwrap();
-    let entity = board.get_from_world(cursor_world);
-    if button.just_pressed(MouseButton::Left) && !button.pressed(MouseButton::Right) {
-        state.set(cursor, *transform, global, camera);
-    } else if button.pressed(MouseButton::Left) && state.check_dragging(cursor) {
+    for &ActionEvent { action, pressed, cursor, source } in events.iter() {
+        let Some(cursor) = cursor else { continue };
+        let Some((entity, transform, global, camera, views, _)) =
+            cameras.iter().find(|&(_, _, _, camera, ..)| in_viewport(camera, cursor))
+        else {
+            continue;
+        };
+        let Ok((board, controller)) = boards.get(views.0) else { continue };
+        if !controller.mouse() {
+            state.camera = None;
+            continue;
+        }
+        let Ok(cursor_world) = camera.viewport_to_world_2d(global, cursor) else { continue };
+        let (board, coordinates) = (views.0, board.get_from_world(cursor_world));
+        match (action, pressed) {
+            (Action::Chord, true) => {
+                state.chord_held = true;
+                state.chorded = true;
+            },
+            (Action::Chord, false) => {
+                state.chord_held = false;
+                if let Some(coordinates) = coordinates {
+                    commands.trigger(Chorded { board, coordinates, source });
+                }
+            },
+            (Action::Reveal | Action::Pan, true) if !state.chord_held => {
+                state.set(entity, cursor, *transform, global, camera);
+            },
+            (Action::Reveal, false) => {
+                if !state.chorded
+                    && !state.check_dragging(cursor)
+                    && let Some(coordinates) = coordinates
+                {
+                    commands.trigger(LeftClicked { board, coordinates, source });
+                }
+            },
+            (Action::Flag, true) if !state.chord_held => {
+                if let Some(coordinates) = coordinates {
+                    commands.trigger(RightClicked { board, coordinates, source });
+                }
+            },
+            _ => {},
+        }
+    }
+    if actions.pressed(Action::Pan)
+        && !state.chorded
+        && let Some(cursor) = window.cursor_position()
+        && state.check_dragging(cursor)
+        && let Some(entity) = state.camera
+        && let Ok((_, mut transform, _, camera, _, limits)) = cameras.get_mut(entity)
+    {
         transform.translation = state.get_translation(cursor, camera);
         limits.limit_translation(&mut transform.translation);
-    } else if button.just_released(MouseButton::Left)
-        && !state.dragging
-        && let Some(entity) = entity
-    {
-        commands.trigger(LeftClicked { entity });
-    } else if button.just_pressed(MouseButton::Right)
-        && let Some(entity) = entity
-    {
-        commands.trigger(RightClicked { entity });
+    }
+}
+
+fn preview_event(
+    cameras: Query<(&GlobalTransform, &Camera, &Views)>, window: Single<&Window>,
+    state: Res<MouseState>, actions: Res<ButtonInput<Action>>,
+    boards: Query<(Entity, &Board, &Controller, &mut PressPreview)>,
+) {
+    let chord = actions.pressed(Action::Chord);
+    let target = window
+        .cursor_position()
+        .filter(|_| chord || (actions.pressed(Action::Reveal) && !state.chorded))
+        .filter(|_| !state.dragging)
+        .and_then(|cursor| {
+            let (global, camera, views) =
+                cameras.iter().find(|&(_, camera, _)| in_viewport(camera, cursor))?;
+            Some((views.0, camera.viewport_to_world_2d(global, cursor).ok()?))
+        });
+    for (entity, board, controller, mut preview) in boards {
+        let coordinates = target
+            .filter(|&(board, _)| board == entity && controller.mouse())
+            .and_then(|(_, world)| board.get_from_world(world));
+        if preview.coordinates != coordinates || preview.chord != chord {
+            *preview = PressPreview { coordinates, chord };
+        }
     }
 }
 
 fn scroll_event(
-    camera: Single<(&mut Transform, &mut Projection, &GlobalTransform, &Camera), With<MainCamera>>,
-    window: Single<&Window>, scroll: Res<AccumulatedMouseScroll>, limits: Res<CameraLimits>,
+    cameras: Query<(&mut Transform, &mut Projection, &GlobalTransform, &Camera, &CameraLimits)>,
+    window: Single<&Window>, scroll: Res<AccumulatedMouseScroll>, events: Res<ActionEvents>,
 ) {
-    if scroll.delta.y == 0.0 {
+    let keys: f32 = events
+        .iter()
+        .filter(|e| e.pressed)
+        .map(|e| match e.action {
+            Action::ZoomIn => 1.0,
+            Action::ZoomOut => -1.0,
+            _ => 0.0,
+        })
+        .sum();
+    let delta = scroll.delta.y + keys;
+    if delta == 0.0 {
         return;
     }
-    let Some(cursor) = window.cursor_position() else { return };
-    let (mut transform, mut projection, global, camera) = camera.into_inner();
+    let cursor = window.cursor_position().unwrap_or(window.size() / 2.0);
+    let Some((mut transform, mut projection, global, camera, limits)) =
+        cameras.into_iter().find(|(_, _, _, camera, _)| in_viewport(camera, cursor))
+    else {
+        return;
+    };
     let mouse_world = camera.viewport_to_world_2d(global, cursor).unwrap().extend(0.0);
     let orthographic = projection.as_orthographic_mut().unwrap();
-    let change = 1.2f32.powf(-scroll.delta.y);
+    let change = 1.2f32.powf(-delta);
     let old = orthographic.scale;
     orthographic.scale *= change;
     limits.limit_scale(&mut orthographic.scale);
@@ -98,6 +210,261 @@ fn despawn(mut commands: Commands) { commands.remove_resource::<MouseState>(); }
 
 pub fn mouse(app: &mut App) {
     app.add_systems(OnEnter(AppState::Playing), spawn)
-        .add_systems(Update, (click_event, scroll_event).run_if(in_state(AppState::Playing)))
+        .add_systems(
+            Update,
+            (
+                (click_event, preview_event).chain().run_if(not(resource_exists::<Replay>)),
+                scroll_event,
+            )
+                .run_if(in_state(Pause::Running)),
+        )
         .add_systems(OnEnter(AppState::Menu), despawn);
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::camera::{CameraProjection, ComputedCameraValues, RenderTargetInfo};
+    use bevy::input::mouse::MouseButtonInput;
+    use bevy::input::{ButtonState, InputPlugin};
+    use bevy::state::app::StatesPlugin;
+    use bevy::window::{CursorMoved, WindowEvent, WindowResolution};
+
+    use super::*;
+    use crate::plugins::{Bindings, MainCamera, actions};
+
+    const TILE: f32 = 32.0;
+
+    #[derive(Resource, Default)]
+    struct Log(Vec<(&'static str, Coordinates)>);
+
+    struct Harness {
+        app: App,
+        window: Entity,
+    }
+
+    impl Harness {
+        fn new() -> Self {
+            let mut app = App::new();
+            app.add_plugins((
+                MinimalPlugins,
+                InputPlugin,
+                StatesPlugin,
+                WindowPlugin {
+                    primary_window: Some(Window {
+                        resolution: WindowResolution::new(640, 480),
+                        ..default()
+                    }),
+                    ..default()
+                },
+                actions,
+                mouse,
+            ))
+            .init_state::<AppState>()
+            .add_sub_state::<Pause>()
+            .insert_resource(Bindings::default())
+            .init_resource::<Log>()
+            .add_observer(|e: On<LeftClicked>, mut log: ResMut<Log>| {
+                log.0.push(("left", e.coordinates));
+            })
+            .add_observer(|e: On<RightClicked>, mut log: ResMut<Log>| {
+                log.0.push(("right", e.coordinates));
+            })
+            .add_observer(|e: On<Chorded>, mut log: ResMut<Log>| {
+                log.0.push(("chord", e.coordinates));
+            });
+            let world = app.world_mut();
+            let board = world.spawn((Board::with_dimensions(4, 4), Controller::Any)).id();
+            let mut projection = OrthographicProjection {
+                scale: 1.0 / TILE,
+                ..OrthographicProjection::default_2d()
+            };
+            projection.update(640.0, 480.0);
+            let camera = Camera {
+                computed: ComputedCameraValues {
+                    clip_from_view: projection.get_clip_from_view(),
+                    target_info: Some(RenderTargetInfo {
+                        physical_size: UVec2::new(640, 480),
+                        scale_factor: 1.0,
+                    }),
+                    ..default()
+                },
+                ..default()
+            };
+            let transform = Transform::from_xyz(10.0, -7.5, 0.0);
+            world.spawn((
+                Camera2d,
+                MainCamera,
+                Views(board),
+                CameraLimits::default(),
+                camera,
+                Projection::Orthographic(projection),
+                transform,
+                GlobalTransform::from(transform),
+            ));
+            let window = world.query_filtered::<Entity, With<Window>>().single(world).unwrap();
+            app.world_mut().resource_mut::<NextState<AppState>>().set(AppState::Playing);
+            app.update();
+            Self { app, window }
+        }
+
+        fn send(&mut self, event: impl Into<WindowEvent>) {
+            self.app.world_mut().write_message(event.into());
+        }
+
+        fn move_to(&mut self, x: usize, y: usize) { self.move_by(x, y, Vec2::ZERO); }
+
+        fn move_by(&mut self, x: usize, y: usize, offset: Vec2) {
+            let position = (Vec2::new(x as f32, y as f32) + 0.5) * TILE + offset;
+            self.send(CursorMoved { window: self.window, position, delta: None });
+        }
+
+        fn button(&mut self, button: MouseButton, state: ButtonState) {
+            self.send(MouseButtonInput { button, state, window: self.window });
+        }
+
+        fn click(&mut self, button: MouseButton) {
+            self.button(button, ButtonState::Pressed);
+            self.button(button, ButtonState::Released);
+        }
+
+        fn frame(&mut self) -> Vec<(&'static str, Coordinates)> {
+            self.app.update();
+            std::mem::take(&mut self.app.world_mut().resource_mut::<Log>().0)
+        }
+    }
+
+    #[test]
+    fn press_and_release_in_one_frame_reveals() {
+        let mut h = Harness::new();
+        h.move_to(2, 1);
+        h.click(MouseButton::Left);
+        assert_eq!(h.frame(), [("left", Coordinates::new(2, 1))]);
+        assert_eq!(h.frame(), []);
+    }
+
+    #[test]
+    fn every_click_in_a_frame_uses_its_own_position() {
+        let mut h = Harness::new();
+        h.move_to(0, 0);
+        h.click(MouseButton::Left);
+        h.move_to(3, 2);
+        h.click(MouseButton::Right);
+        h.move_to(1, 3);
+        h.click(MouseButton::Left);
+        assert_eq!(h.frame(), [
+            ("left", Coordinates::new(0, 0)),
+            ("right", Coordinates::new(3, 2)),
+            ("left", Coordinates::new(1, 3))
+        ]);
+    }
+
+    #[test]
+    fn press_and_release_across_frames_reveals_once() {
+        let mut h = Harness::new();
+        h.move_to(1, 1);
+        h.button(MouseButton::Left, ButtonState::Pressed);
+        assert_eq!(h.frame(), []);
+        h.button(MouseButton::Left, ButtonState::Released);
+        assert_eq!(h.frame(), [("left", Coordinates::new(1, 1))]);
+    }
+
+    #[test]
+    fn both_buttons_chord_once() {
+        let mut h = Harness::new();
+        h.move_to(2, 2);
+        h.button(MouseButton::Left, ButtonState::Pressed);
+        h.button(MouseButton::Right, ButtonState::Pressed);
+        h.button(MouseButton::Right, ButtonState::Released);
+        h.button(MouseButton::Left, ButtonState::Released);
+        assert_eq!(h.frame(), [("chord", Coordinates::new(2, 2))]);
+    }
+
+    #[test]
+    fn chord_fires_on_release_where_the_cursor_is() {
+        let mut h = Harness::new();
+        h.move_to(0, 0);
+        h.button(MouseButton::Left, ButtonState::Pressed);
+        h.button(MouseButton::Right, ButtonState::Pressed);
+        assert_eq!(h.frame(), []);
+        h.move_to(3, 3);
+        h.button(MouseButton::Left, ButtonState::Released);
+        h.button(MouseButton::Right, ButtonState::Released);
+        assert_eq!(h.frame(), [("chord", Coordinates::new(3, 3))]);
+    }
+
+    #[test]
+    fn right_before_left_flags_then_chords() {
+        let mut h = Harness::new();
+        h.move_to(1, 2);
+        h.button(MouseButton::Right, ButtonState::Pressed);
+        h.button(MouseButton::Left, ButtonState::Pressed);
+        h.button(MouseButton::Left, ButtonState::Released);
+        h.button(MouseButton::Right, ButtonState::Released);
+        assert_eq!(h.frame(), [
+            ("right", Coordinates::new(1, 2)),
+            ("chord", Coordinates::new(1, 2))
+        ]);
+    }
+
+    #[test]
+    fn drag_suppresses_reveal() {
+        let mut h = Harness::new();
+        h.move_to(1, 1);
+        h.button(MouseButton::Left, ButtonState::Pressed);
+        h.move_by(1, 1, Vec2::new(6.0, 0.0));
+        h.button(MouseButton::Left, ButtonState::Released);
+        assert_eq!(h.frame(), []);
+    }
+
+    #[test]
+    fn small_jitter_still_reveals() {
+        let mut h = Harness::new();
+        h.move_to(1, 1);
+        h.button(MouseButton::Left, ButtonState::Pressed);
+        h.move_by(1, 1, Vec2::new(2.0, 2.0));
+        h.button(MouseButton::Left, ButtonState::Released);
+        assert_eq!(h.frame(), [("left", Coordinates::new(1, 1))]);
+    }
+
+    #[test]
+    fn state_resets_after_drag_and_chord() {
+        let mut h = Harness::new();
+        h.move_to(0, 0);
+        h.button(MouseButton::Left, ButtonState::Pressed);
+        h.move_to(2, 0);
+        h.button(MouseButton::Left, ButtonState::Released);
+        h.button(MouseButton::Left, ButtonState::Pressed);
+        h.button(MouseButton::Right, ButtonState::Pressed);
+        h.button(MouseButton::Left, ButtonState::Released);
+        h.button(MouseButton::Right, ButtonState::Released);
+        h.click(MouseButton::Right);
+        h.click(MouseButton::Left);
+        assert_eq!(h.frame(), [
+            ("chord", Coordinates::new(2, 0)),
+            ("right", Coordinates::new(2, 0)),
+            ("left", Coordinates::new(2, 0))
+        ]);
+    }
+
+    #[test]
+    fn left_handed_swaps_buttons() {
+        let mut h = Harness::new();
+        h.app.world_mut().resource_mut::<Bindings>().left_handed = true;
+        h.move_to(3, 0);
+        h.click(MouseButton::Left);
+        h.click(MouseButton::Right);
+        assert_eq!(h.frame(), [
+            ("right", Coordinates::new(3, 0)),
+            ("left", Coordinates::new(3, 0))
+        ]);
+    }
+
+    #[test]
+    fn clicks_outside_the_board_are_ignored() {
+        let mut h = Harness::new();
+        h.move_to(7, 7);
+        h.click(MouseButton::Left);
+        h.click(MouseButton::Right);
+        assert_eq!(h.frame(), []);
+    }
+}
@@ -0,0 +1,142 @@
+use bevy::camera::visibility::RenderLayers;
+use bevy::prelude::*;
+
+use crate::AppState;
+use crate::plugins::main_menu::Cycling;
+use crate::plugins::{
+    Board, CameraLimits, Localized, Owners, RegionRevealed, ThemeColor, Turns, Views,
+};
+
+#[derive(Component, Copy, Clone, PartialEq, Eq)]
+pub enum Mode {
+    Solo,
+    Race,
+    Flags,
+}
+
+impl Cycling for Mode {
+    fn next(self) -> Self {
+        match self {
+            Mode::Solo => Mode::Race,
+            Mode::Race => Mode::Flags,
+            Mode::Flags => Mode::Solo,
+        }
+    }
+
+    fn color(self) -> ThemeColor {
+        match self {
+            Mode::Solo => ThemeColor::Text,
+            Mode::Race => ThemeColor::Accent,
+            Mode::Flags => ThemeColor::Hard,
+        }
+    }
+
+    fn label(self) -> &'static str { "mode" }
+
+    fn value(self) -> Localized {
+        Localized::new(match self {
+            Mode::Solo => "mode-solo",
+            Mode::Race => "mode-race",
+            Mode::Flags => "mode-flags",
+        })
+    }
+}
+
+#[derive(Component, Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Player(pub usize);
+
+#[derive(Component, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Controller {
+    Any,
+    Mouse,
+    Keyboard,
+    Gamepad(Entity),
+}
+
+impl Controller {
+    #[must_use]
+    pub fn mouse(self) -> bool { matches!(self, Controller::Any | Controller::Mouse) }
+
+    #[must_use]
+    pub fn keyboard(self) -> bool { matches!(self, Controller::Any | Controller::Keyboard) }
+
+    #[must_use]
+    pub fn gamepad(self, gamepad: Entity) -> bool {
+        self == Controller::Any || self == Controller::Gamepad(gamepad)
+    }
+}
+
+pub fn controllers(mode: Mode, gamepads: &[Entity]) -> Vec<Controller> {
+    match (mode, gamepads) {
+        (Mode::Solo | Mode::Flags, _) => vec![Controller::Any],
+        (Mode::Race, [a, b, ..]) => vec![Controller::Gamepad(*a), Controller::Gamepad(*b)],
+        (Mode::Race, _) => vec![Controller::Mouse, Controller::Keyboard],
+    }
+}
+
+pub fn spawn_players(
+    commands: &mut Commands, board: Board, mode: Mode, main_camera: Entity, gamepads: &[Entity],
+) -> Vec<Entity> {
+    let mut boards = Vec::new();
+    for (i, controller) in controllers(mode, gamepads).into_iter().enumerate() {
+        let layers = RenderLayers::layer(i);
+        let entity = commands
+            .spawn((
+                board.clone(),
+                Player(i),
+                controller,
+                layers.clone(),
+                DespawnOnEnter(AppState::Menu),
+            ))
+            .id();
+        if mode == Mode::Flags {
+            commands.entity(entity).insert((Turns::default(), Owners::default()));
+        }
+        boards.push(entity);
+        let view = (Views(entity), layers, CameraLimits::default());
+        if i == 0 {
+            commands.entity(main_camera).insert(view);
+        } else {
+            commands.spawn((
+                Camera2d,
+                Camera { order: i as isize, ..default() },
+                view,
+                DespawnOnEnter(AppState::Menu),
+            ));
+        }
+    }
+    boards
+}
+
+fn eliminate(revealed: On<RegionRevealed>, mut boards: Query<&mut Board>) {
+    if boards.iter().count() < 2 || boards.iter().all(Board::is_exploded) {
+        return;
+    }
+    let Ok(mut board) = boards.get_mut(revealed.board) else { return };
+    if board.is_exploded() {
+        board.finish(false);
+    }
+}
+
+pub fn race(app: &mut App) { app.add_observer(eliminate); }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn race_controllers_prefer_two_gamepads() {
+        let (a, b) = (Entity::from_raw_u32(1).unwrap(), Entity::from_raw_u32(2).unwrap());
+        assert_eq!(controllers(Mode::Solo, &[a, b]), [Controller::Any]);
+        assert_eq!(controllers(Mode::Flags, &[a, b]), [Controller::Any]);
+        assert_eq!(controllers(Mode::Race, &[a]), [Controller::Mouse, Controller::Keyboard]);
+        assert_eq!(controllers(Mode::Race, &[a, b]), [
+            Controller::Gamepad(a),
+            Controller::Gamepad(b)
+        ]);
+        assert!(
+            Controller::Any.mouse() && Controller::Any.keyboard() && Controller::Any.gamepad(a)
+        );
+        assert!(!Controller::Mouse.keyboard() && !Controller::Gamepad(b).gamepad(a));
+    }
+}
@@ -0,0 +1,285 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{env, fs};
+
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use bevy::window::WindowEvent;
+
+use crate::plugins::actions::swap_hands;
+use crate::plugins::{
+    Action, Bindings, Board, Chorded, Clicks, Coordinates, Elapsed, LeftClicked, MainCamera, Mode,
+    Remote, RightClicked, Source, Theme, ThemeColor, Turns, spawn_players,
+};
+use crate::rules::Click;
+use crate::utils::config_path;
+use crate::video::{Buttons, MouseEvent, SQUARE, Video, VideoEvent, playback_board, timestamp};
+use crate::{AppState, Pause};
+
+const DRAG_DISTANCE: f32 = 5.0;
+const OFF_BOARD: (i32, i32) = (-1, -1);
+
+fn mouse_event(button: MouseButton, state: ButtonState) -> Option<MouseEvent> {
+    Some(match (button, state) {
+        (MouseButton::Left, ButtonState::Pressed) => MouseEvent::LeftPress,
+        (MouseButton::Left, ButtonState::Released) => MouseEvent::LeftRelease,
+        (MouseButton::Right, ButtonState::Pressed) => MouseEvent::RightPress,
+        (MouseButton::Right, ButtonState::Released) => MouseEvent::RightRelease,
+        (MouseButton::Middle, ButtonState::Pressed) => MouseEvent::MiddlePress,
+        (MouseButton::Middle, ButtonState::Released) => MouseEvent::MiddleRelease,
+        _ => return None,
+    })
+}
+
+#[derive(Resource)]
+pub struct Replay {
+    video: Video,
+    started: bool,
+    next: usize,
+    buttons: Buttons,
+}
+
+#[derive(Resource, Default)]
+struct Recorder {
+    events: Vec<VideoEvent>,
+    start: Option<f32>,
+    cursor: Option<Vec2>,
+    press: Option<Vec2>,
+}
+
+impl Recorder {
+    fn time(&self, elapsed: f32) -> f32 { self.start.map_or(0.0, |start| elapsed - start) }
+
+    fn push(&mut self, elapsed: f32, mouse: MouseEvent, (x, y): (i32, i32)) {
+        if matches!(mouse, MouseEvent::LeftPress | MouseEvent::RightPress | MouseEvent::MiddlePress)
+        {
+            self.start.get_or_insert(elapsed);
+        }
+        let time = self.time(elapsed);
+        self.events.push(VideoEvent { time, mouse, x, y });
+    }
+
+    fn click(
+        &mut self, elapsed: f32, source: Source, coordinates: Coordinates, mice: &[MouseEvent],
+    ) {
+        if !matches!(source, Source::Keyboard | Source::Touch) {
+            return;
+        }
+        let position = (coordinates.x * SQUARE + SQUARE / 2, coordinates.y * SQUARE + SQUARE / 2);
+        for &mouse in mice {
+            self.push(elapsed, mouse, position);
+        }
+    }
+}
+
+#[derive(Component)]
+struct ReplayCursor;
+
+fn import() -> Option<Video> {
+    let path = env::args().skip_while(|arg| arg != "--replay").nth(1)?;
+    let video = fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|t| Video::parse(&t));
+    video.inspect_err(|e| warn!("Failed to import {path}: {e}")).ok()
+}
+
+fn start(
+    mut commands: Commands, mut replay: ResMut<Replay>,
+    mut next_state: ResMut<NextState<AppState>>, camera: Single<Entity, With<MainCamera>>,
+) {
+    if replay.started {
+        commands.remove_resource::<Replay>();
+        return;
+    }
+    replay.started = true;
+    let board = playback_board(replay.video.layout.clone());
+    spawn_players(&mut commands, board, Mode::Solo, *camera, &[]);
+    next_state.set(AppState::Playing);
+}
+
+fn reset(
+    mut commands: Commands, replay: Option<ResMut<Replay>>, theme: Res<Theme>,
+    cursor: Query<(), With<ReplayCursor>>,
+    boards: Query<(), (With<Board>, Without<Remote>, Without<Turns>)>,
+) {
+    let Some(mut replay) = replay else {
+        if boards.iter().count() == 1 {
+            commands.insert_resource(Recorder::default());
+        }
+        return;
+    };
+    replay.next = 0;
+    replay.buttons = Buttons::default();
+    if !cursor.is_empty() {
+        return;
+    }
+    commands.spawn((
+        Sprite::from_color(theme.color(ThemeColor::Text), Vec2::splat(0.3)),
+        Transform::from_xyz(0.0, 0.0, 2.0),
+        ReplayCursor,
+        DespawnOnEnter(AppState::Menu),
+    ));
+}
+
+fn play(
+    mut commands: Commands, mut replay: ResMut<Replay>, elapsed: Res<Elapsed>,
+    mut cursor: Single<&mut Transform, With<ReplayCursor>>, board: Single<Entity, With<Board>>,
+) {
+    let (board, source) = (*board, Source::Replay);
+    let replay = &mut *replay;
+    while let Some(&event) = replay.video.events.get(replay.next)
+        && event.time <= elapsed.0
+    {
+        replay.next += 1;
+        cursor.translation.x = event.x as f32 / SQUARE as f32;
+        cursor.translation.y = -event.y as f32 / SQUARE as f32;
+        let coordinates = event.coordinates();
+        match replay.buttons.apply(event.mouse) {
+            Some(Click::Left) => commands.trigger(LeftClicked { board, coordinates, source }),
+            Some(Click::Right) => commands.trigger(RightClicked { board, coordinates, source }),
+            Some(Click::Chord) => commands.trigger(Chorded { board, coordinates, source }),
+            None => {},
+        }
+    }
+}
+
+fn record_mouse(
+    mut events: MessageReader<WindowEvent>,
+    camera: Single<(&GlobalTransform, &Camera), With<MainCamera>>, board: Single<&Board>,
+    bindings: Res<Bindings>, elapsed: Res<Elapsed>, mut recorder: ResMut<Recorder>,
+) {
+    let (global, camera) = camera.into_inner();
+    let bound = |button| {
+        [Action::Reveal, Action::Flag, Action::Chord]
+            .iter()
+            .filter_map(|action| bindings.actions.get(action))
+            .any(|binding| binding.uses(button))
+    };
+    for event in events.read() {
+        let (mouse, cursor) = match event {
+            WindowEvent::CursorMoved(moved) => (MouseEvent::Move, moved.position),
+            WindowEvent::MouseButtonInput(input) => {
+                let button = swap_hands(input.button, bindings.left_handed);
+                let Some(mouse) = mouse_event(button, input.state).filter(|_| bound(button)) else {
+                    continue;
+                };
+                let Some(cursor) = recorder.cursor else { continue };
+                (mouse, cursor)
+            },
+            _ => continue,
+        };
+        recorder.cursor = Some(cursor);
+        let Ok(world) = camera.viewport_to_world_2d(global, cursor) else { continue };
+        let dragged = mouse == MouseEvent::LeftRelease
+            && !recorder.press.take().is_some_and(|p| p.distance(cursor) < DRAG_DISTANCE);
+        let position = if dragged {
+            OFF_BOARD
+        } else {
+            ((world.x * SQUARE as f32) as i32, (-world.y * SQUARE as f32) as i32)
+        };
+        match mouse {
+            MouseEvent::Move
+                if board.get_from_world(world).is_none()
+                    || recorder.events.last().is_some_and(|e| (e.x, e.y) == position) =>
+            {
+                continue;
+            },
+            MouseEvent::LeftPress => recorder.press = Some(cursor),
+            _ => {},
+        }
+        recorder.push(elapsed.0, mouse, position);
+    }
+}
+
+fn record_left(
+    clicked: On<LeftClicked>, recorder: Option<ResMut<Recorder>>, elapsed: Res<Elapsed>,
+) {
+    let Some(mut recorder) = recorder else { return };
+    let mice = [MouseEvent::LeftPress, MouseEvent::LeftRelease];
+    recorder.click(elapsed.0, clicked.source, clicked.coordinates, &mice);
+}
+
+fn record_right(
+    clicked: On<RightClicked>, recorder: Option<ResMut<Recorder>>, elapsed: Res<Elapsed>,
+) {
+    let Some(mut recorder) = recorder else { return };
+    let mice = [MouseEvent::RightPress, MouseEvent::RightRelease];
+    recorder.click(elapsed.0, clicked.source, clicked.coordinates, &mice);
+}
+
+fn record_chord(chorded: On<Chorded>, recorder: Option<ResMut<Recorder>>, elapsed: Res<Elapsed>) {
+    let Some(mut recorder) = recorder else { return };
+    let mice = [MouseEvent::MiddlePress, MouseEvent::MiddleRelease];
+    recorder.click(elapsed.0, chorded.source, chorded.coordinates, &mice);
+}
+
+fn save(
+    recorder: Res<Recorder>, board: Single<(&Board, &Clicks)>, elapsed: Res<Elapsed>,
+    bindings: Res<Bindings>, state: Res<State<AppState>>,
+) {
+    let (board, clicks) = board.into_inner();
+    let Some(layout) = board.export(false) else { return };
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let video = Video {
+        bbbv: Some(layout.bbbv()),
+        clicks: Some(*clicks),
+        flag_chord: bindings.flag_chord,
+        layout,
+        player: env::var("USER").or_else(|_| env::var("USERNAME")).unwrap_or_default(),
+        timestamp: timestamp(now),
+        time: recorder.time(elapsed.0),
+        won: **state == AppState::Won,
+        events: recorder.events.clone(),
+    };
+    let name = video.timestamp.replace([' ', ':'], "-");
+    let Some(path) = config_path(&format!("replays/{name}.rawvf")) else { return };
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::write(&path, video.to_string()));
+    match result {
+        Ok(()) => info!("Saved replay to {}", path.display()),
+        Err(e) => warn!("Failed to save {}: {e}", path.display()),
+    }
+}
+
+fn despawn(mut commands: Commands) { commands.remove_resource::<Recorder>(); }
+
+pub fn replay(app: &mut App) {
+    if let Some(video) = import() {
+        app.insert_resource(Replay { video, started: false, next: 0, buttons: default() });
+    }
+    app.add_systems(Update, start.run_if(in_state(AppState::Menu).and(resource_exists::<Replay>)))
+        .add_systems(OnEnter(AppState::Playing), reset)
+        .add_systems(
+            PreUpdate,
+            record_mouse.run_if(in_state(Pause::Running).and(resource_exists::<Recorder>)),
+        )
+        .add_systems(Update, play.run_if(in_state(Pause::Running).and(resource_exists::<Replay>)))
+        .add_observer(record_left)
+        .add_observer(record_right)
+        .add_observer(record_chord)
+        .add_systems(OnEnter(AppState::Won), save.run_if(resource_exists::<Recorder>))
+        .add_systems(OnEnter(AppState::Lost), save.run_if(resource_exists::<Recorder>))
+        .add_systems(OnEnter(AppState::Menu), despawn);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MouseEvent::{
+        LeftPress, LeftRelease, MiddlePress, MiddleRelease, Move, RightPress, RightRelease,
+    };
+    use super::*;
+
+    #[test]
+    fn recorder_starts_the_clock_at_the_first_press() {
+        let mut recorder = Recorder::default();
+        recorder.push(1.0, Move, (8, 8));
+        recorder.push(2.5, LeftPress, (8, 8));
+        recorder.push(3.0, LeftRelease, (8, 8));
+        recorder.click(4.0, Source::Touch, Coordinates::new(1, 0), &[RightPress, RightRelease]);
+        let times: Vec<_> = recorder.events.iter().map(|e| e.time).collect();
+        assert_eq!(times, [0.0, 0.0, 0.5, 1.5, 1.5]);
+        assert_eq!((recorder.events[4].x, recorder.events[4].y), (24, 8));
+        recorder.click(5.0, Source::Mouse, Coordinates::new(1, 0), &[LeftPress, LeftRelease]);
+        recorder.click(5.0, Source::Rules, Coordinates::new(1, 0), &[MiddlePress, MiddleRelease]);
+        assert_eq!((recorder.events.len(), recorder.time(5.0)), (5, 2.5));
+    }
+}
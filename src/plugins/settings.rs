@@ -0,0 +1,736 @@
+use std::marker::PhantomData;
+
+use bevy::ecs::relationship::RelatedSpawnerCommands;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::AppState;
+use crate::plugins::actions::{Binding, swap_hands};
+use crate::plugins::main_menu::{
+    Cycling, MenuButton, button_base, buttons_hover, cycling_button, cycling_click,
+};
+use crate::plugins::{
+    Action, Bindings, Colorblind, GameAssets, GameSounds, Languages, Locale, Localized, SoundPacks,
+    TextValSize, TexturePacks, Theme, ThemeColor, ThemedBackground, ThemedText, Themes,
+};
+use crate::utils::Persistent;
+
+const LONG_PRESSES: [u16; 5] = [300, 500, 800, 1200, 2000];
+
+#[derive(Resource, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    pub theme: String,
+    pub texture_pack: String,
+    pub colorblind: Colorblind,
+    pub shape_cues: bool,
+    pub reduce_motion: bool,
+    pub sound_pack: String,
+    pub volume: u8,
+    pub muted: bool,
+    pub language: String,
+    pub long_press_ms: u16,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            theme: String::from("Nord"),
+            texture_pack: String::from("Nord"),
+            colorblind: Colorblind::Off,
+            shape_cues: false,
+            reduce_motion: false,
+            sound_pack: String::from("Default"),
+            volume: 70,
+            muted: false,
+            language: String::from("en-US"),
+            long_press_ms: 500,
+        }
+    }
+}
+
+impl Persistent for Preferences {
+    const FILE: &'static str = "preferences.ron";
+}
+
+#[derive(Component, Copy, Clone)]
+enum Handedness {
+    Right,
+    Left,
+}
+
+impl Cycling for Handedness {
+    fn next(self) -> Self {
+        match self {
+            Handedness::Right => Handedness::Left,
+            Handedness::Left => Handedness::Right,
+        }
+    }
+
+    fn color(self) -> ThemeColor { ThemeColor::Accent }
+
+    fn label(self) -> &'static str { "handedness" }
+
+    fn value(self) -> Localized {
+        Localized::new(match self {
+            Handedness::Right => "handedness-right",
+            Handedness::Left => "handedness-left",
+        })
+    }
+}
+
+trait Switch: Send + Sync + 'static {
+    type Target: Resource;
+    const LABEL: &'static str;
+
+    fn field(target: &mut Self::Target) -> &mut bool;
+}
+
+#[derive(Component)]
+struct Toggle<S: Switch> {
+    on: bool,
+    switch: PhantomData<S>,
+}
+
+impl<S: Switch> Toggle<S> {
+    fn new(on: bool) -> Self { Self { on, switch: PhantomData } }
+}
+
+impl<S: Switch> Clone for Toggle<S> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<S: Switch> Copy for Toggle<S> {}
+
+impl<S: Switch> Cycling for Toggle<S> {
+    fn next(self) -> Self { Self::new(!self.on) }
+
+    fn color(self) -> ThemeColor {
+        if self.on { ThemeColor::Positive } else { ThemeColor::Negative }
+    }
+
+    fn label(self) -> &'static str { S::LABEL }
+
+    fn value(self) -> Localized { Localized::new(if self.on { "on" } else { "off" }) }
+}
+
+struct FlagChord;
+
+impl Switch for FlagChord {
+    type Target = Bindings;
+
+    const LABEL: &'static str = "flag-chord";
+
+    fn field(bindings: &mut Bindings) -> &mut bool { &mut bindings.flag_chord }
+}
+
+struct ShapeCues;
+
+impl Switch for ShapeCues {
+    type Target = Preferences;
+
+    const LABEL: &'static str = "shape-cues";
+
+    fn field(preferences: &mut Preferences) -> &mut bool { &mut preferences.shape_cues }
+}
+
+struct ReduceMotion;
+
+impl Switch for ReduceMotion {
+    type Target = Preferences;
+
+    const LABEL: &'static str = "reduce-motion";
+
+    fn field(preferences: &mut Preferences) -> &mut bool { &mut preferences.reduce_motion }
+}
+
+struct Mute;
+
+impl Switch for Mute {
+    type Target = Preferences;
+
+    const LABEL: &'static str = "mute";
+
+    fn field(preferences: &mut Preferences) -> &mut bool { &mut preferences.muted }
+}
+
+#[derive(Component, Copy, Clone, PartialEq, Eq)]
+enum ChordStyle {
+    Both,
+    Middle,
+    Custom,
+}
+
+impl ChordStyle {
+    fn of(binding: Option<&Binding>) -> Self {
+        match binding {
+            Some(
+                Binding::MousePair(MouseButton::Left, MouseButton::Right)
+                | Binding::MousePair(MouseButton::Right, MouseButton::Left),
+            ) => ChordStyle::Both,
+            Some(Binding::Mouse(MouseButton::Middle)) => ChordStyle::Middle,
+            _ => ChordStyle::Custom,
+        }
+    }
+
+    fn binding(self) -> Option<Binding> {
+        match self {
+            ChordStyle::Both => Some(Binding::MousePair(MouseButton::Left, MouseButton::Right)),
+            ChordStyle::Middle => Some(Binding::Mouse(MouseButton::Middle)),
+            ChordStyle::Custom => None,
+        }
+    }
+}
+
+impl Cycling for ChordStyle {
+    fn next(self) -> Self {
+        match self {
+            ChordStyle::Both => ChordStyle::Middle,
+            ChordStyle::Middle | ChordStyle::Custom => ChordStyle::Both,
+        }
+    }
+
+    fn color(self) -> ThemeColor {
+        match self {
+            ChordStyle::Both | ChordStyle::Middle => ThemeColor::Accent,
+            ChordStyle::Custom => ThemeColor::Text,
+        }
+    }
+
+    fn label(self) -> &'static str { "chord-style" }
+
+    fn value(self) -> Localized {
+        Localized::new(match self {
+            ChordStyle::Both => "chord-style-both",
+            ChordStyle::Middle => "chord-style-middle",
+            ChordStyle::Custom => "chord-style-custom",
+        })
+    }
+}
+
+impl Cycling for Colorblind {
+    fn next(self) -> Self {
+        match self {
+            Colorblind::Off => Colorblind::Protanopia,
+            Colorblind::Protanopia => Colorblind::Deuteranopia,
+            Colorblind::Deuteranopia => Colorblind::Tritanopia,
+            Colorblind::Tritanopia => Colorblind::Off,
+        }
+    }
+
+    fn color(self) -> ThemeColor { ThemeColor::Accent }
+
+    fn label(self) -> &'static str { "colorblind" }
+
+    fn value(self) -> Localized {
+        Localized::new(match self {
+            Colorblind::Off => "off",
+            Colorblind::Protanopia => "colorblind-protanopia",
+            Colorblind::Deuteranopia => "colorblind-deuteranopia",
+            Colorblind::Tritanopia => "colorblind-tritanopia",
+        })
+    }
+}
+
+#[derive(Component, Copy, Clone)]
+struct SoundVolume(u8);
+
+impl Cycling for SoundVolume {
+    fn next(self) -> Self { SoundVolume((self.0 / 10 + 1) % 11 * 10) }
+
+    fn color(self) -> ThemeColor { ThemeColor::Accent }
+
+    fn label(self) -> &'static str { "volume" }
+
+    fn value(self) -> Localized { Localized::new("volume-percent").with("percent", self.0) }
+}
+
+#[derive(Component, Copy, Clone)]
+struct LongPress(u16);
+
+impl Cycling for LongPress {
+    fn next(self) -> Self {
+        let next = LONG_PRESSES.into_iter().find(|&ms| ms > self.0);
+        LongPress(next.unwrap_or(LONG_PRESSES[0]))
+    }
+
+    fn color(self) -> ThemeColor { ThemeColor::Accent }
+
+    fn label(self) -> &'static str { "long-press" }
+
+    fn value(self) -> Localized {
+        Localized::new("long-press-seconds").with("seconds", f64::from(self.0) / 1000.0)
+    }
+}
+
+#[derive(Component)]
+struct Rebind(Action);
+
+#[derive(Component)]
+struct ThemeChoice;
+
+#[derive(Component)]
+struct TexturePackChoice;
+
+#[derive(Component)]
+struct SoundPackChoice;
+
+#[derive(Component)]
+struct LanguageChoice;
+
+#[derive(Component)]
+struct Reset;
+
+#[derive(Component)]
+struct Back;
+
+#[derive(Resource, Default)]
+struct Listening(Option<Action>);
+
+fn binding_text(
+    action: Action, bindings: &Bindings, listening: &Listening, locale: &Locale,
+) -> String {
+    let name = locale.text(action.message());
+    if listening.0 == Some(action) {
+        locale.format("settings-listening", [("action", name.into())])
+    } else {
+        let binding = bindings.display(action, locale);
+        locale.format("settings-binding", [("action", name.into()), ("binding", binding.into())])
+    }
+}
+
+fn text_button<M: Bundle>(
+    parent: &mut RelatedSpawnerCommands<ChildOf>, height: Val, text: impl Bundle, marker: M,
+) {
+    parent.spawn(button_base(height, marker)).with_child((
+        text,
+        ThemedText(ThemeColor::Text),
+        TextValSize(Val::Percent(60.0)),
+    ));
+}
+
+fn column() -> Node {
+    Node {
+        width: Val::Percent(50.0),
+        height: Val::Percent(100.0),
+        align_items: AlignItems::Center,
+        justify_content: JustifyContent::SpaceBetween,
+        flex_direction: FlexDirection::Column,
+        ..default()
+    }
+}
+
+fn rebind_buttons(
+    parent: &mut RelatedSpawnerCommands<ChildOf>, bindings: &Bindings, locale: &Locale,
+) {
+    let height = Val::Percent(92.0 / (Action::ALL.len() + 1) as f32);
+    for action in Action::ALL {
+        let text = binding_text(action, bindings, &Listening(None), locale);
+        text_button(parent, height, Text::new(text), Rebind(action));
+    }
+    text_button(parent, height, (Text::default(), Localized::new("reset")), Reset);
+}
+
+fn spawn(
+    mut commands: Commands, bindings: Res<Bindings>, preferences: Res<Preferences>,
+    theme: Res<Theme>, assets: Res<GameAssets>, sounds: Res<GameSounds>, locale: Res<Locale>,
+) {
+    commands.insert_resource(Listening::default());
+    let handedness = if bindings.left_handed { Handedness::Left } else { Handedness::Right };
+    let chord_style = ChordStyle::of(bindings.actions.get(&Action::Chord));
+    let flag_chord = Toggle::<FlagChord>::new(bindings.flag_chord);
+    let shape_cues = Toggle::<ShapeCues>::new(preferences.shape_cues);
+    let reduce_motion = Toggle::<ReduceMotion>::new(preferences.reduce_motion);
+    let mute = Toggle::<Mute>::new(preferences.muted);
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ThemedBackground(ThemeColor::Background),
+            DespawnOnExit(AppState::Settings),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(Node {
+                    width: Val::Percent(90.0),
+                    height: Val::VMin(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::SpaceBetween,
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::vertical(Val::Percent(3.0)),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::default(),
+                        Localized::new("settings"),
+                        ThemedText(ThemeColor::Text),
+                        TextValSize(Val::Percent(8.0)),
+                    ));
+                    parent
+                        .spawn(Node {
+                            width: Val::Percent(100.0),
+                            height: Val::Percent(78.0),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            parent
+                                .spawn(column())
+                                .with_children(|parent| rebind_buttons(parent, &bindings, &locale));
+                            parent.spawn(column()).with_children(|parent| {
+                                cycling_button(parent, Val::Percent(7.0), handedness);
+                                cycling_button(parent, Val::Percent(7.0), chord_style);
+                                cycling_button(parent, Val::Percent(7.0), flag_chord);
+                                let long_press = LongPress(preferences.long_press_ms);
+                                cycling_button(parent, Val::Percent(7.0), long_press);
+                                let text = theme_text(&theme, &locale);
+                                text_button(
+                                    parent,
+                                    Val::Percent(7.0),
+                                    Text::new(text),
+                                    ThemeChoice,
+                                );
+                                let text = pack_text(&assets, &locale);
+                                text_button(
+                                    parent,
+                                    Val::Percent(7.0),
+                                    Text::new(text),
+                                    TexturePackChoice,
+                                );
+                                cycling_button(parent, Val::Percent(7.0), preferences.colorblind);
+                                cycling_button(parent, Val::Percent(7.0), shape_cues);
+                                cycling_button(parent, Val::Percent(7.0), reduce_motion);
+                                let text = sound_pack_text(&sounds, &locale);
+                                text_button(
+                                    parent,
+                                    Val::Percent(7.0),
+                                    Text::new(text),
+                                    SoundPackChoice,
+                                );
+                                let volume = SoundVolume(preferences.volume);
+                                cycling_button(parent, Val::Percent(7.0), volume);
+                                cycling_button(parent, Val::Percent(7.0), mute);
+                                let text = language_text(&locale);
+                                text_button(
+                                    parent,
+                                    Val::Percent(7.0),
+                                    Text::new(text),
+                                    LanguageChoice,
+                                );
+                            });
+                        });
+                    parent.spawn(button_base(Val::Percent(6.0), Back)).with_child((
+                        Text::default(),
+                        Localized::new("back"),
+                        ThemedText(ThemeColor::Text),
+                        TextValSize(Val::Percent(60.0)),
+                    ));
+                });
+        });
+}
+
+fn theme_text(theme: &Theme, locale: &Locale) -> String {
+    locale.format("settings-theme", [("name", theme.name.as_str().into())])
+}
+
+fn pack_text(assets: &GameAssets, locale: &Locale) -> String {
+    locale.format("settings-tiles", [("name", assets.pack.as_str().into())])
+}
+
+fn sound_pack_text(sounds: &GameSounds, locale: &Locale) -> String {
+    locale.format("settings-sounds", [("name", sounds.pack.as_str().into())])
+}
+
+fn language_text(locale: &Locale) -> String {
+    locale.format("settings-language", [("name", locale.name.as_str().into())])
+}
+
+fn rebind_click(
+    interactions: Query<(&Interaction, &Rebind), (Changed<Interaction>, With<MenuButton>)>,
+    mut listening: ResMut<Listening>,
+) {
+    if listening.0.is_some() {
+        return;
+    }
+    for (interaction, rebind) in interactions {
+        if *interaction == Interaction::Pressed {
+            listening.0 = Some(rebind.0);
+        }
+    }
+}
+
+fn capture(
+    mut listening: ResMut<Listening>, mut bindings: ResMut<Bindings>,
+    keys: Res<ButtonInput<KeyCode>>, mouse: Res<ButtonInput<MouseButton>>,
+) {
+    let Some(action) = listening.0 else { return };
+    if listening.is_changed() {
+        return;
+    }
+    let logical = |b| swap_hands(b, bindings.left_handed);
+    let binding = if let Some(&key) = keys.get_just_pressed().next() {
+        if key == KeyCode::Escape {
+            listening.0 = None;
+            return;
+        }
+        Binding::Key(key)
+    } else if let Some(&button) = mouse.get_just_pressed().next() {
+        match mouse.get_pressed().find(|&&b| b != button) {
+            Some(&held) => Binding::MousePair(logical(held), logical(button)),
+            None => Binding::Mouse(logical(button)),
+        }
+    } else {
+        return;
+    };
+    bindings.rebind(action, binding);
+    listening.0 = None;
+}
+
+fn update_texts(
+    rebinds: Query<(&Rebind, &Children)>, mut texts: Query<&mut Text>, bindings: Res<Bindings>,
+    listening: Res<Listening>, locale: Res<Locale>,
+) {
+    for (rebind, children) in rebinds {
+        for &e in children {
+            let Ok(mut text) = texts.get_mut(e) else { continue };
+            text.0 = binding_text(rebind.0, &bindings, &listening, &locale);
+        }
+    }
+}
+
+fn apply_handedness(
+    handedness: Single<&Handedness, Changed<Handedness>>, mut bindings: ResMut<Bindings>,
+) {
+    let left_handed = matches!(**handedness, Handedness::Left);
+    if bindings.left_handed != left_handed {
+        bindings.left_handed = left_handed;
+    }
+}
+
+fn apply_chord_style(
+    chord_style: Single<&ChordStyle, Changed<ChordStyle>>, mut bindings: ResMut<Bindings>,
+) {
+    let Some(binding) = chord_style.binding() else { return };
+    if bindings.actions.get(&Action::Chord) != Some(&binding) {
+        bindings.actions.insert(Action::Chord, binding);
+    }
+}
+
+fn sync_chord_style(mut chord_style: Single<&mut ChordStyle>, bindings: Res<Bindings>) {
+    let style = ChordStyle::of(bindings.actions.get(&Action::Chord));
+    if **chord_style != style {
+        **chord_style = style;
+    }
+}
+
+fn theme_click(
+    interaction: Single<&Interaction, (Changed<Interaction>, With<MenuButton>, With<ThemeChoice>)>,
+    listening: Res<Listening>, themes: Res<Themes>, mut preferences: ResMut<Preferences>,
+) {
+    if **interaction == Interaction::Pressed && listening.0.is_none() {
+        preferences.theme = themes.next(&preferences.theme).name.clone();
+    }
+}
+
+fn update_theme_text(
+    children: Single<&Children, With<ThemeChoice>>, mut texts: Query<&mut Text>, theme: Res<Theme>,
+    locale: Res<Locale>,
+) {
+    for &e in *children {
+        let Ok(mut text) = texts.get_mut(e) else { continue };
+        text.0 = theme_text(&theme, &locale);
+    }
+}
+
+fn pack_click(
+    interaction: Single<
+        &Interaction,
+        (Changed<Interaction>, With<MenuButton>, With<TexturePackChoice>),
+    >,
+    listening: Res<Listening>, packs: Res<TexturePacks>, mut preferences: ResMut<Preferences>,
+) {
+    if **interaction == Interaction::Pressed && listening.0.is_none() {
+        preferences.texture_pack = packs.next(&preferences.texture_pack).name.clone();
+    }
+}
+
+fn update_pack_text(
+    children: Single<&Children, With<TexturePackChoice>>, mut texts: Query<&mut Text>,
+    assets: Res<GameAssets>, locale: Res<Locale>,
+) {
+    for &e in *children {
+        let Ok(mut text) = texts.get_mut(e) else { continue };
+        text.0 = pack_text(&assets, &locale);
+    }
+}
+
+fn sound_pack_click(
+    interaction: Single<
+        &Interaction,
+        (Changed<Interaction>, With<MenuButton>, With<SoundPackChoice>),
+    >,
+    listening: Res<Listening>, packs: Res<SoundPacks>, mut preferences: ResMut<Preferences>,
+) {
+    if **interaction == Interaction::Pressed && listening.0.is_none() {
+        preferences.sound_pack = packs.next(&preferences.sound_pack).name.clone();
+    }
+}
+
+fn update_sound_pack_text(
+    children: Single<&Children, With<SoundPackChoice>>, mut texts: Query<&mut Text>,
+    sounds: Res<GameSounds>, locale: Res<Locale>,
+) {
+    for &e in *children {
+        let Ok(mut text) = texts.get_mut(e) else { continue };
+        text.0 = sound_pack_text(&sounds, &locale);
+    }
+}
+
+fn language_click(
+    interaction: Single<
+        &Interaction,
+        (Changed<Interaction>, With<MenuButton>, With<LanguageChoice>),
+    >,
+    listening: Res<Listening>, languages: Res<Languages>, mut preferences: ResMut<Preferences>,
+) {
+    if **interaction == Interaction::Pressed && listening.0.is_none() {
+        preferences.language = languages.next(&preferences.language).id.to_owned();
+    }
+}
+
+fn update_language_text(
+    children: Single<&Children, With<LanguageChoice>>, mut texts: Query<&mut Text>,
+    locale: Res<Locale>,
+) {
+    for &e in *children {
+        let Ok(mut text) = texts.get_mut(e) else { continue };
+        text.0 = language_text(&locale);
+    }
+}
+
+fn apply_colorblind(
+    colorblind: Single<&Colorblind, Changed<Colorblind>>, mut preferences: ResMut<Preferences>,
+) {
+    if preferences.colorblind != **colorblind {
+        preferences.colorblind = **colorblind;
+    }
+}
+
+fn apply_toggle<S: Switch>(
+    toggle: Single<&Toggle<S>, Changed<Toggle<S>>>, mut target: ResMut<S::Target>,
+) {
+    if *S::field(target.bypass_change_detection()) != toggle.on {
+        *S::field(&mut target) = toggle.on;
+    }
+}
+
+fn apply_volume(
+    volume: Single<&SoundVolume, Changed<SoundVolume>>, mut preferences: ResMut<Preferences>,
+) {
+    if preferences.volume != volume.0 {
+        preferences.volume = volume.0;
+    }
+}
+
+fn apply_long_press(
+    long_press: Single<&LongPress, Changed<LongPress>>, mut preferences: ResMut<Preferences>,
+) {
+    if preferences.long_press_ms != long_press.0 {
+        preferences.long_press_ms = long_press.0;
+    }
+}
+
+fn reset_click(
+    interaction: Single<&Interaction, (Changed<Interaction>, With<MenuButton>, With<Reset>)>,
+    listening: Res<Listening>, mut bindings: ResMut<Bindings>,
+) {
+    if **interaction == Interaction::Pressed && listening.0.is_none() {
+        *bindings = Bindings {
+            left_handed: bindings.left_handed,
+            flag_chord: bindings.flag_chord,
+            ..default()
+        };
+    }
+}
+
+fn back_click(
+    interaction: Single<&Interaction, (Changed<Interaction>, With<MenuButton>, With<Back>)>,
+    listening: Res<Listening>, mut next_state: ResMut<NextState<AppState>>,
+) {
+    if **interaction == Interaction::Pressed && listening.0.is_none() {
+        next_state.set(AppState::Menu);
+    }
+}
+
+fn despawn(mut commands: Commands, bindings: Res<Bindings>, preferences: Res<Preferences>) {
+    bindings.save();
+    preferences.save();
+    commands.remove_resource::<Listening>();
+}
+
+pub fn settings(app: &mut App) {
+    app.insert_resource(Preferences::load())
+        .add_systems(OnEnter(AppState::Settings), spawn)
+        .add_systems(
+            Update,
+            (
+                buttons_hover,
+                (
+                    cycling_click::<Handedness>,
+                    cycling_click::<ChordStyle>,
+                    cycling_click::<Toggle<FlagChord>>,
+                    cycling_click::<Colorblind>,
+                    cycling_click::<Toggle<ShapeCues>>,
+                    cycling_click::<Toggle<ReduceMotion>>,
+                    cycling_click::<SoundVolume>,
+                    cycling_click::<Toggle<Mute>>,
+                    cycling_click::<LongPress>,
+                ),
+                (
+                    apply_handedness,
+                    apply_chord_style,
+                    apply_toggle::<FlagChord>,
+                    apply_colorblind,
+                    apply_toggle::<ShapeCues>,
+                    apply_toggle::<ReduceMotion>,
+                    apply_volume,
+                    apply_toggle::<Mute>,
+                    apply_long_press,
+                ),
+                (
+                    rebind_click,
+                    theme_click,
+                    pack_click,
+                    sound_pack_click,
+                    language_click,
+                    reset_click,
+                    back_click,
+                    capture,
+                )
+                    .chain(),
+                (
+                    update_texts.run_if(
+                        resource_changed::<Bindings>
+                            .or(resource_changed::<Listening>)
+                            .or(resource_changed::<Locale>),
+                    ),
+                    update_theme_text
+                        .run_if(resource_changed::<Theme>.or(resource_changed::<Locale>)),
+                    update_pack_text
+                        .run_if(resource_changed::<GameAssets>.or(resource_changed::<Locale>)),
+                    update_sound_pack_text
+                        .run_if(resource_changed::<GameSounds>.or(resource_changed::<Locale>)),
+                    update_language_text.run_if(resource_changed::<Locale>),
+                    sync_chord_style.run_if(resource_changed::<Bindings>),
+                ),
+            )
+                .chain()
+                .run_if(in_state(AppState::Settings)),
+        )
+        .add_systems(OnExit(AppState::Settings), despawn);
+}
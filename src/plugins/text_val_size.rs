@@ -6,7 +6,7 @@ pub struct TextValSize(pub Val);
 fn update(
     texts: Query<(&TextValSize, &mut TextFont, &ChildOf, Option<&Children>), With<Text>>,
     nodes: Query<&ComputedNode, Changed<ComputedNode>>,
-    mut spans: Query<&mut TextFont, (With<TextSpan>, Without<TextValSize>)>
+    mut spans: Query<&mut TextFont, (With<TextSpan>, Without<TextValSize>)>,
 ) {
     for (val, mut font, child_of, children) in texts {
         let Ok(parent) = nodes.get(child_of.parent()) else { continue };
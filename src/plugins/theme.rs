@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+use std::fs;
+
+use bevy::color::HexColorError;
+use bevy::ecs::component::Mutable;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::plugins::Preferences;
+use crate::utils::config_path;
+
+const BUILT_IN: [&str; 3] = [
+    include_str!("../../assets/themes/nord.ron"),
+    include_str!("../../assets/themes/light.ron"),
+    include_str!("../../assets/themes/high_contrast.ron"),
+];
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Deserialize)]
+pub enum ThemeColor {
+    Background,
+    Panel,
+    Hover,
+    Border,
+    HoverBorder,
+    Text,
+    Track,
+    Accent,
+    Positive,
+    Negative,
+    Easy,
+    Medium,
+    Hard,
+    Extreme,
+    Giant,
+    Colossal,
+    Debug,
+    One,
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+}
+
+impl ThemeColor {
+    pub const ALL: [Self; 25] = [
+        Self::Background,
+        Self::Panel,
+        Self::Hover,
+        Self::Border,
+        Self::HoverBorder,
+        Self::Text,
+        Self::Track,
+        Self::Accent,
+        Self::Positive,
+        Self::Negative,
+        Self::Easy,
+        Self::Medium,
+        Self::Hard,
+        Self::Extreme,
+        Self::Giant,
+        Self::Colossal,
+        Self::Debug,
+        Self::One,
+        Self::Two,
+        Self::Three,
+        Self::Four,
+        Self::Five,
+        Self::Six,
+        Self::Seven,
+        Self::Eight,
+    ];
+    pub const NUMBERS: [Self; 8] = [
+        Self::One,
+        Self::Two,
+        Self::Three,
+        Self::Four,
+        Self::Five,
+        Self::Six,
+        Self::Seven,
+        Self::Eight,
+    ];
+}
+
+#[derive(Component, Copy, Clone, PartialEq, Eq, Default, Debug, Serialize, Deserialize)]
+pub enum Colorblind {
+    #[default]
+    Off,
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl Colorblind {
+    fn overrides(self) -> &'static [(ThemeColor, &'static str)] {
+        match self {
+            Colorblind::Off => &[],
+            Colorblind::Protanopia => &[
+                (ThemeColor::Positive, "#56b4e9"),
+                (ThemeColor::Negative, "#e69f00"),
+                (ThemeColor::Easy, "#56b4e9"),
+                (ThemeColor::Medium, "#f0e442"),
+                (ThemeColor::Hard, "#e69f00"),
+                (ThemeColor::Extreme, "#a06000"),
+                (ThemeColor::Giant, "#0072b2"),
+                (ThemeColor::Colossal, "#000080"),
+                (ThemeColor::Debug, "#cc79a7"),
+                (ThemeColor::One, "#56b4e9"),
+                (ThemeColor::Two, "#f0e442"),
+                (ThemeColor::Three, "#e69f00"),
+                (ThemeColor::Four, "#0050a0"),
+                (ThemeColor::Five, "#a06000"),
+                (ThemeColor::Six, "#9ad0f5"),
+                (ThemeColor::Seven, "#000000"),
+                (ThemeColor::Eight, "#8c8c8c"),
+            ],
+            Colorblind::Deuteranopia => &[
+                (ThemeColor::Positive, "#0072b2"),
+                (ThemeColor::Negative, "#d55e00"),
+                (ThemeColor::Easy, "#0072b2"),
+                (ThemeColor::Medium, "#f0e442"),
+                (ThemeColor::Hard, "#d55e00"),
+                (ThemeColor::Extreme, "#7a2e00"),
+                (ThemeColor::Giant, "#332288"),
+                (ThemeColor::Colossal, "#1a1a60"),
+                (ThemeColor::Debug, "#cc79a7"),
+                (ThemeColor::One, "#0072b2"),
+                (ThemeColor::Two, "#d55e00"),
+                (ThemeColor::Three, "#cc79a7"),
+                (ThemeColor::Four, "#332288"),
+                (ThemeColor::Five, "#7a2e00"),
+                (ThemeColor::Six, "#44aa99"),
+                (ThemeColor::Seven, "#1a1a1a"),
+                (ThemeColor::Eight, "#b0b0b0"),
+            ],
+            Colorblind::Tritanopia => &[
+                (ThemeColor::Positive, "#009e9e"),
+                (ThemeColor::Negative, "#e8384f"),
+                (ThemeColor::Easy, "#009e9e"),
+                (ThemeColor::Medium, "#ff9eb1"),
+                (ThemeColor::Hard, "#e8384f"),
+                (ThemeColor::Extreme, "#8b0030"),
+                (ThemeColor::Giant, "#5c0018"),
+                (ThemeColor::Colossal, "#2e2e2e"),
+                (ThemeColor::Debug, "#a0a0a0"),
+                (ThemeColor::One, "#009e9e"),
+                (ThemeColor::Two, "#e8384f"),
+                (ThemeColor::Three, "#ff9eb1"),
+                (ThemeColor::Four, "#8b0030"),
+                (ThemeColor::Five, "#1f6f6f"),
+                (ThemeColor::Six, "#a0a0a0"),
+                (ThemeColor::Seven, "#000000"),
+                (ThemeColor::Eight, "#5c5c5c"),
+            ],
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+struct Hex(Color);
+
+impl TryFrom<String> for Hex {
+    type Error = HexColorError;
+
+    fn try_from(hex: String) -> Result<Self, Self::Error> { Ok(Self(Srgba::hex(hex)?.into())) }
+}
+
+#[derive(Resource, Clone, PartialEq, Deserialize)]
+pub struct Theme {
+    pub name: String,
+    colors: HashMap<ThemeColor, Hex>,
+}
+
+impl Theme {
+    #[must_use]
+    pub fn color(&self, slot: ThemeColor) -> Color { self.colors[&slot].0 }
+
+    fn with_colorblind(mut self, colorblind: Colorblind) -> Self {
+        for &(slot, hex) in colorblind.overrides() {
+            self.colors.insert(slot, Hex(Srgba::hex(hex).unwrap().into()));
+        }
+        self
+    }
+}
+
+#[derive(Resource)]
+pub struct Themes(Vec<Theme>);
+
+impl Themes {
+    fn built_in() -> Self {
+        Self(
+            BUILT_IN
+                .iter()
+                .map(|text| ron::from_str(text).expect("Invalid built-in theme"))
+                .collect(),
+        )
+    }
+
+    fn load() -> Self {
+        let Self(mut themes) = Self::built_in();
+        let dir = config_path("themes").and_then(|dir| fs::read_dir(dir).ok());
+        for path in dir.into_iter().flatten().flatten().map(|entry| entry.path()) {
+            if path.extension().is_none_or(|e| e != "ron") {
+                continue;
+            }
+            let Ok(text) = fs::read_to_string(&path) else { continue };
+            let mut theme: Theme = match ron::from_str(&text) {
+                Ok(theme) => theme,
+                Err(e) => {
+                    warn!("Failed to parse {}: {e}", path.display());
+                    continue;
+                },
+            };
+            for slot in ThemeColor::ALL {
+                theme.colors.entry(slot).or_insert(themes[0].colors[&slot]);
+            }
+            themes.retain(|t| t.name != theme.name);
+            themes.push(theme);
+        }
+        Self(themes)
+    }
+
+    #[must_use]
+    pub fn get(&self, name: &str) -> &Theme {
+        self.0.iter().find(|t| t.name == name).unwrap_or(&self.0[0])
+    }
+
+    #[must_use]
+    pub fn next(&self, name: &str) -> &Theme {
+        let index = self.0.iter().position(|t| t.name == name).map_or(0, |i| i + 1);
+        &self.0[index % self.0.len()]
+    }
+}
+
+trait Themed: Component + Copy {
+    type Target: Component<Mutability = Mutable>;
+
+    fn slot(self) -> ThemeColor;
+
+    fn paint(color: Color) -> Self::Target;
+}
+
+#[derive(Component, Copy, Clone)]
+#[require(TextColor)]
+pub struct ThemedText(pub ThemeColor);
+
+impl Themed for ThemedText {
+    type Target = TextColor;
+
+    fn slot(self) -> ThemeColor { self.0 }
+
+    fn paint(color: Color) -> TextColor { TextColor(color) }
+}
+
+#[derive(Component, Copy, Clone)]
+#[require(BackgroundColor)]
+pub struct ThemedBackground(pub ThemeColor);
+
+impl Themed for ThemedBackground {
+    type Target = BackgroundColor;
+
+    fn slot(self) -> ThemeColor { self.0 }
+
+    fn paint(color: Color) -> BackgroundColor { BackgroundColor(color) }
+}
+
+#[derive(Component, Copy, Clone)]
+#[require(BorderColor)]
+pub struct ThemedBorder(pub ThemeColor);
+
+impl Themed for ThemedBorder {
+    type Target = BorderColor;
+
+    fn slot(self) -> ThemeColor { self.0 }
+
+    fn paint(color: Color) -> BorderColor { BorderColor::all(color) }
+}
+
+fn select(
+    mut commands: Commands, themes: Res<Themes>, preferences: Res<Preferences>,
+    theme: Option<Res<Theme>>,
+) {
+    let selected = themes.get(&preferences.theme).clone().with_colorblind(preferences.colorblind);
+    if theme.is_some_and(|t| *t == selected) {
+        return;
+    }
+    commands.insert_resource(ClearColor(selected.color(ThemeColor::Background)));
+    commands.insert_resource(selected);
+}
+
+fn paint<T: Themed>(theme: Res<Theme>, themed: Query<(Ref<T>, &mut T::Target)>) {
+    for (themed, mut target) in themed {
+        if theme.is_changed() || themed.is_changed() {
+            *target = T::paint(theme.color(themed.slot()));
+        }
+    }
+}
+
+pub fn theme(app: &mut App) {
+    app.insert_resource(Themes::load())
+        .add_systems(Update, select.run_if(resource_changed::<Preferences>))
+        .add_systems(
+            PostUpdate,
+            (paint::<ThemedText>, paint::<ThemedBackground>, paint::<ThemedBorder>)
+                .run_if(resource_exists::<Theme>),
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_themes_define_every_color() {
+        let themes = Themes::built_in();
+        for text in BUILT_IN {
+            let theme: Theme = ron::from_str(text).unwrap();
+            assert!(ThemeColor::ALL.iter().all(|slot| theme.colors.contains_key(slot)));
+            assert_eq!(themes.get(&theme.name).name, theme.name);
+        }
+        assert_eq!(themes.next("High contrast").name, "Nord");
+        for colorblind in [Colorblind::Protanopia, Colorblind::Deuteranopia, Colorblind::Tritanopia]
+        {
+            assert!(themes.get("Nord").clone().with_colorblind(colorblind) != *themes.get("Nord"));
+            let numbers = ThemeColor::NUMBERS.map(|slot| {
+                colorblind.overrides().iter().find(|(s, _)| *s == slot).map(|(_, hex)| *hex)
+            });
+            assert!(numbers.iter().all(Option::is_some));
+            assert!(numbers.iter().enumerate().all(|(i, n)| !numbers[..i].contains(n)));
+        }
+        let protan = themes.get("Nord").clone().with_colorblind(Colorblind::Protanopia);
+        assert!(protan != themes.get("Nord").clone().with_colorblind(Colorblind::Deuteranopia));
+    }
+}
@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+
+use bevy::asset::RenderAssetUsages;
+use bevy::camera::visibility::RenderLayers;
+use bevy::prelude::*;
+use bevy::render::render_resource::{
+    AsBindGroup, Extent3d, ShaderType, TextureDimension, TextureFormat,
+};
+use bevy::shader::ShaderRef;
+use bevy::sprite_render::{
+    AlphaMode2d, Material2d, Material2dPlugin, TileData, TilemapChunk, TilemapChunkTileData,
+};
+
+use crate::AppState;
+use crate::plugins::game_assets::Tiles;
+use crate::plugins::{
+    Animations, Board, Colorblind, Face, GameAssets, Preferences, Theme, ThemeColor,
+};
+use crate::rules::CHUNK;
+
+#[derive(ShaderType, Clone)]
+struct Palette {
+    covered: Vec4,
+    uncovered: Vec4,
+    grid: Vec4,
+    flag: Vec4,
+    mine: Vec4,
+    clicked: Vec4,
+    numbers: [Vec4; 8],
+    cues: u32,
+}
+
+impl Palette {
+    fn new(theme: &Theme, preferences: &Preferences) -> Self {
+        let color = |slot| LinearRgba::from(theme.color(slot)).to_vec4();
+        Self {
+            covered: color(ThemeColor::Track),
+            uncovered: color(ThemeColor::Hover),
+            grid: color(ThemeColor::Background),
+            flag: color(ThemeColor::Negative),
+            mine: color(ThemeColor::Text),
+            clicked: color(ThemeColor::Negative),
+            numbers: ThemeColor::NUMBERS.map(color),
+            cues: u32::from(preferences.shape_cues),
+        }
+    }
+}
+
+#[derive(Asset, TypePath, AsBindGroup, Clone)]
+struct VectorTiles {
+    #[texture(0, sample_type = "u_int")]
+    faces: Handle<Image>,
+    #[uniform(1)]
+    palette: Palette,
+}
+
+impl Material2d for VectorTiles {
+    fn fragment_shader() -> ShaderRef { "shaders/vector_tiles.wgsl".into() }
+}
+
+#[derive(Clone, PartialEq)]
+struct Style {
+    pack: String,
+    numbers: Option<[Color; 8]>,
+    cues: bool,
+}
+
+impl Style {
+    fn new(assets: &GameAssets, theme: &Theme, preferences: &Preferences) -> Self {
+        Self {
+            pack: assets.pack.clone(),
+            numbers: (preferences.colorblind != Colorblind::Off)
+                .then(|| ThemeColor::NUMBERS.map(|slot| theme.color(slot))),
+            cues: preferences.shape_cues,
+        }
+    }
+}
+
+#[derive(Resource)]
+enum Renderer {
+    Atlas { style: Style, image: Handle<Image> },
+    Vector { pack: String, mesh: Handle<Mesh> },
+}
+
+impl Renderer {
+    fn matches(&self, style: &Style) -> bool {
+        match self {
+            Renderer::Atlas { style: current, .. } => current == style,
+            Renderer::Vector { pack, .. } => *pack == style.pack,
+        }
+    }
+}
+
+#[derive(Component)]
+struct Chunk {
+    board: Entity,
+    x: i32,
+    y: i32,
+}
+
+impl Chunk {
+    fn tilemap(image: &Handle<Image>) -> TilemapChunk {
+        TilemapChunk {
+            chunk_size: UVec2::splat(CHUNK as u32),
+            tile_display_size: UVec2::ONE,
+            tileset: image.clone(),
+            alpha_mode: AlphaMode2d::Opaque,
+        }
+    }
+
+    fn tile_data(&self, board: &Board, animations: &Animations) -> TilemapChunkTileData {
+        let mut data = vec![None; (CHUNK * CHUNK) as usize];
+        for c in board.chunk_cells(self.x, self.y) {
+            data[c.index()] =
+                Some(TileData::from_tileset_index(animations.face(board, c).index() as u16));
+        }
+        TilemapChunkTileData(data)
+    }
+
+    fn faces(&self, board: &Board, animations: &Animations) -> Vec<u8> {
+        let mut faces = vec![u16::MAX; (CHUNK * CHUNK) as usize];
+        for c in board.chunk_cells(self.x, self.y) {
+            faces[c.index()] = match animations.face(board, c) {
+                Face::Covered => 0,
+                Face::Pressed => 1,
+                Face::Flagged => 2,
+                Face::Empty => 3,
+                Face::Bomb => 4,
+                Face::BombClicked => 5,
+                Face::WrongFlag => 6,
+                Face::Neighbours(n) => 16 + u16::from(n),
+            };
+        }
+        faces.into_iter().flat_map(u16::to_le_bytes).collect()
+    }
+
+    fn center(&self) -> Vec3 {
+        let center = Vec2::new(self.x as f32 + 0.5, -(self.y as f32) - 0.5) * CHUNK as f32;
+        center.extend(0.0)
+    }
+}
+
+fn paint(data: &mut [u8], face: Face, base: Face, f: impl Fn(usize, f32, &mut [u8], &[u8])) {
+    let len = data.len() / Face::ALL.len();
+    let base = data[base.index() * len..][..len].to_vec();
+    let layer = &mut data[face.index() * len..][..len];
+    let diffs: Vec<_> = layer
+        .chunks_exact(4)
+        .zip(base.chunks_exact(4))
+        .map(|(p, b)| (0..3).map(|c| p[c].abs_diff(b[c])).max().unwrap_or(0))
+        .collect();
+    let max = f32::from(diffs.iter().copied().max().unwrap_or(0).max(1));
+    for (i, (pixel, base)) in layer.chunks_exact_mut(4).zip(base.chunks_exact(4)).enumerate() {
+        f(i, f32::from(diffs[i]) / max, pixel, base);
+    }
+}
+
+fn restyle(data: &mut [u8], width: usize, style: &Style) {
+    if let Some(numbers) = style.numbers {
+        for (n, color) in (1..).zip(numbers) {
+            let target = color.to_srgba().to_u8_array();
+            paint(data, Face::Neighbours(n), Face::Empty, |_, coverage, pixel, base| {
+                for c in 0..3 {
+                    let (from, to) = (f32::from(base[c]), f32::from(target[c]));
+                    pixel[c] = (from + (to - from) * coverage) as u8;
+                }
+            });
+        }
+    }
+    if style.cues {
+        let stripe = |i: usize| ((i % width + i / width) * 8 / width).is_multiple_of(2);
+        let darken = |i: usize, coverage: f32, pixel: &mut [u8], _: &[u8]| {
+            if coverage > 0.5 && stripe(i) {
+                for c in &mut pixel[..3] {
+                    *c = (f32::from(*c) * 0.55) as u8;
+                }
+            }
+        };
+        paint(data, Face::Flagged, Face::Covered, darken);
+        paint(data, Face::BombClicked, Face::Bomb, darken);
+        let hatch = |i: usize, coverage: f32, pixel: &mut [u8], _: &[u8]| {
+            let (x, y) = (i % width, i / width);
+            let cross = ((x + y) * 8 / width).is_multiple_of(2)
+                ^ ((width - 1 - x + y) * 8 / width).is_multiple_of(2);
+            if coverage > 0.5 && cross {
+                for c in &mut pixel[..3] {
+                    *c = 255 - *c;
+                }
+            }
+        };
+        paint(data, Face::WrongFlag, Face::Covered, hatch);
+    }
+}
+
+fn build_atlas(
+    images: &mut Assets<Image>, sources: &[Handle<Image>], style: &Style,
+) -> Option<Handle<Image>> {
+    let sources: Option<Vec<_>> = sources
+        .iter()
+        .map(|handle| images.get(handle)?.convert(TextureFormat::Rgba8UnormSrgb))
+        .collect();
+    let sources = sources?;
+    let size = sources[0].size();
+    let layers = Face::ALL.len() as u32;
+    if sources.iter().any(|s| s.size() != size || s.data.is_none()) {
+        warn!("Tile images must all have the same size");
+        return None;
+    }
+    let height = size.y * sources.len() as u32;
+    if !height.is_multiple_of(layers) {
+        warn!("Tile atlas height must be a multiple of {layers} tiles");
+        return None;
+    }
+    let mut data: Vec<_> = sources.into_iter().flat_map(|s| s.data.unwrap()).collect();
+    restyle(&mut data, size.x as usize, style);
+    let mut image = Image::new(
+        Extent3d { width: size.x, height, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.reinterpret_stacked_2d_as_array(layers);
+    Some(images.add(image))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn prepare(
+    mut commands: Commands, assets: Res<GameAssets>, theme: Res<Theme>,
+    preferences: Res<Preferences>, renderer: Option<Res<Renderer>>,
+    mut images: ResMut<Assets<Image>>, mut meshes: ResMut<Assets<Mesh>>,
+    chunks: Query<Entity, With<Chunk>>, boards: Query<&mut Board>,
+) {
+    let style = Style::new(&assets, &theme, &preferences);
+    if renderer.is_some_and(|r| r.matches(&style)) {
+        return;
+    }
+    let renderer = match &assets.tiles {
+        Tiles::Images(sources) => {
+            let Some(image) = build_atlas(&mut images, sources, &style) else { return };
+            Renderer::Atlas { style, image }
+        },
+        Tiles::Vector => {
+            let mesh = meshes.add(Rectangle::from_length(CHUNK as f32));
+            Renderer::Vector { pack: style.pack, mesh }
+        },
+    };
+    for entity in chunks {
+        commands.entity(entity).despawn();
+    }
+    for mut board in boards {
+        board.redraw();
+    }
+    commands.insert_resource(renderer);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_chunks(
+    mut commands: Commands, boards: Query<(Entity, &mut Board, &Animations, &RenderLayers)>,
+    renderer: Res<Renderer>, theme: Res<Theme>, preferences: Res<Preferences>,
+    chunks: Query<(Entity, &Chunk, Option<&MeshMaterial2d<VectorTiles>>)>,
+    mut images: ResMut<Assets<Image>>, mut materials: ResMut<Assets<VectorTiles>>,
+) {
+    let existing: HashMap<_, _> = chunks
+        .iter()
+        .map(|(entity, chunk, material)| ((chunk.board, chunk.x, chunk.y), (entity, material)))
+        .collect();
+    for (board_entity, mut board, animations, layers) in boards {
+        if !board.is_dirty() {
+            continue;
+        }
+        for (x, y) in board.take_dirty() {
+            let chunk = Chunk { board: board_entity, x, y };
+            match (&*renderer, existing.get(&(board_entity, x, y))) {
+                (Renderer::Atlas { .. }, Some(&(entity, _))) => {
+                    commands.entity(entity).insert(chunk.tile_data(&board, animations));
+                },
+                (Renderer::Vector { .. }, Some(&(_, Some(material)))) => {
+                    let Some(faces) = materials.get(material).map(|m| m.faces.id()) else {
+                        continue;
+                    };
+                    let Some(image) = images.get_mut(faces) else { continue };
+                    image.data = Some(chunk.faces(&board, animations));
+                },
+                (Renderer::Atlas { image, .. }, None) => {
+                    commands.spawn((
+                        Chunk::tilemap(image),
+                        chunk.tile_data(&board, animations),
+                        Transform::from_translation(chunk.center()),
+                        chunk,
+                        layers.clone(),
+                        DespawnOnEnter(AppState::Menu),
+                    ));
+                },
+                (Renderer::Vector { mesh, .. }, _) => {
+                    let faces = images.add(Image::new(
+                        Extent3d {
+                            width: CHUNK as u32,
+                            height: CHUNK as u32,
+                            depth_or_array_layers: 1,
+                        },
+                        TextureDimension::D2,
+                        chunk.faces(&board, animations),
+                        TextureFormat::R16Uint,
+                        RenderAssetUsages::default(),
+                    ));
+                    let material =
+                        VectorTiles { faces, palette: Palette::new(&theme, &preferences) };
+                    commands.spawn((
+                        Mesh2d(mesh.clone()),
+                        MeshMaterial2d(materials.add(material)),
+                        Transform::from_translation(chunk.center()),
+                        chunk,
+                        layers.clone(),
+                        DespawnOnEnter(AppState::Menu),
+                    ));
+                },
+            }
+        }
+    }
+}
+
+fn repaint(
+    theme: Res<Theme>, preferences: Res<Preferences>, mut materials: ResMut<Assets<VectorTiles>>,
+) {
+    for (_, material) in materials.iter_mut() {
+        material.palette = Palette::new(&theme, &preferences);
+    }
+}
+
+pub fn tilemap(app: &mut App) {
+    app.add_plugins(Material2dPlugin::<VectorTiles>::default())
+        .add_systems(
+            Update,
+            prepare.run_if(resource_exists::<GameAssets>.and(resource_exists::<Theme>)),
+        )
+        .add_systems(
+            PostUpdate,
+            (
+                update_chunks.run_if(resource_exists::<Renderer>),
+                repaint.run_if(
+                    resource_exists::<Theme>
+                        .and(resource_changed::<Theme>.or(resource_changed::<Preferences>)),
+                ),
+            ),
+        );
+}
@@ -0,0 +1,304 @@
+use bevy::input::touch::Touch;
+use bevy::prelude::*;
+
+use crate::plugins::mouse::{MouseState, in_viewport};
+use crate::plugins::{
+    Board, CameraLimits, Controller, LeftClicked, Preferences, Replay, RightClicked, Source, Views,
+};
+use crate::utils::ProjectionExt;
+use crate::{AppState, Pause};
+
+#[derive(Default)]
+enum Gesture {
+    #[default]
+    Idle,
+    Press {
+        id: u64,
+        start: f32,
+        long_pressed: bool,
+    },
+    Pinch,
+    Cancelled,
+}
+
+#[derive(Resource, Default)]
+struct TouchState {
+    gesture: Gesture,
+    mouse: MouseState,
+}
+
+fn spawn(mut commands: Commands) { commands.insert_resource(TouchState::default()); }
+
+fn press_event(
+    mut commands: Commands,
+    mut cameras: Query<(Entity, &mut Transform, &GlobalTransform, &Camera, &Views, &CameraLimits)>,
+    touches: Res<Touches>, time: Res<Time>, mut state: ResMut<TouchState>,
+    preferences: Res<Preferences>, boards: Query<(&Board, &Controller)>,
+) {
+    let pressed = touches.iter().count();
+    let TouchState { gesture, mouse } = &mut *state;
+    match gesture {
+        Gesture::Idle => {
+            if pressed > 1 {
+                *gesture = Gesture::Pinch;
+            } else if let Some(touch) =
+                touches.iter_just_pressed().chain(touches.iter_just_released()).next()
+                && let Some((entity, transform, global, camera, views, _)) =
+                    cameras.iter().find(|&(_, _, _, camera, views, _)| {
+                        in_viewport(camera, touch.position())
+                            && boards.get(views.0).is_ok_and(|(_, controller)| controller.mouse())
+                    })
+            {
+                mouse.set(entity, touch.position(), *transform, global, camera);
+                if !touches.just_released(touch.id()) {
+                    *gesture = Gesture::Press {
+                        id: touch.id(),
+                        start: time.elapsed_secs(),
+                        long_pressed: false,
+                    };
+                } else if let Ok((board, _)) = boards.get(views.0)
+                    && let Some(coordinates) = board.get_from_world(mouse.world_start)
+                {
+                    commands.trigger(LeftClicked {
+                        board: views.0,
+                        coordinates,
+                        source: Source::Touch,
+                    });
+                }
+            }
+        },
+        Gesture::Press { id, start, long_pressed } => {
+            let Some(Ok((_, mut transform, _, camera, views, limits))) =
+                mouse.camera.map(|entity| cameras.get_mut(entity))
+            else {
+                *gesture = Gesture::Cancelled;
+                return;
+            };
+            let (entity, board) = (views.0, boards.get(views.0).map(|(board, _)| board));
+            let Ok(board) = board else { return };
+            if pressed > 1 {
+                *gesture = Gesture::Pinch;
+            } else if let Some(touch) = touches.get_pressed(*id) {
+                if mouse.check_dragging(touch.position()) {
+                    transform.translation = mouse.get_translation(touch.position(), camera);
+                    limits.limit_translation(&mut transform.translation);
+                } else if !*long_pressed
+                    && time.elapsed_secs() - *start >= f32::from(preferences.long_press_ms) / 1000.0
+                {
+                    *long_pressed = true;
+                    if let Some(coordinates) = board.get_from_world(mouse.world_start) {
+                        commands.trigger(RightClicked {
+                            board: entity,
+                            coordinates,
+                            source: Source::Touch,
+                        });
+                    }
+                }
+            } else {
+                if let Some(touch) = touches.get_released(*id)
+                    && !*long_pressed
+                    && !mouse.check_dragging(touch.position())
+                    && let Some(coordinates) = board.get_from_world(mouse.world_start)
+                {
+                    commands.trigger(LeftClicked {
+                        board: entity,
+                        coordinates,
+                        source: Source::Touch,
+                    });
+                }
+                *gesture = Gesture::Idle;
+            }
+        },
+        Gesture::Pinch => {
+            if pressed < 2 {
+                *gesture = if pressed == 0 { Gesture::Idle } else { Gesture::Cancelled };
+            }
+        },
+        Gesture::Cancelled => {
+            if pressed == 0 {
+                *gesture = Gesture::Idle;
+            }
+        },
+    }
+}
+
+fn pinch_event(
+    cameras: Query<(&mut Transform, &mut Projection, &GlobalTransform, &Camera, &CameraLimits)>,
+    touches: Res<Touches>, state: Res<TouchState>,
+) {
+    let Gesture::Pinch = state.gesture else { return };
+    let mut pressed = touches.iter();
+    let (Some(a), Some(b)) = (pressed.next(), pressed.next()) else { return };
+    let midpoint = |f: fn(&Touch) -> Vec2| (f(a) + f(b)) / 2.0;
+    let distance = |f: fn(&Touch) -> Vec2| f(a).distance(f(b));
+    let (previous, current) = (distance(Touch::previous_position), distance(Touch::position));
+    if previous <= 0.0 || current <= 0.0 {
+        return;
+    }
+    let Some((mut transform, mut projection, global, camera, limits)) = cameras
+        .into_iter()
+        .find(|(_, _, _, camera, _)| in_viewport(camera, midpoint(Touch::position)))
+    else {
+        return;
+    };
+    let start_world = camera.viewport_to_world_2d(global, midpoint(Touch::previous_position));
+    let end_world = camera.viewport_to_world_2d(global, midpoint(Touch::position));
+    let (Ok(start_world), Ok(end_world)) = (start_world, end_world) else { return };
+    let orthographic = projection.as_orthographic_mut().unwrap();
+    let old = orthographic.scale;
+    orthographic.scale *= previous / current;
+    limits.limit_scale(&mut orthographic.scale);
+    let start_world = start_world.extend(0.0);
+    let translation = transform.translation + start_world - end_world.extend(0.0);
+    transform.translation = start_world - (start_world - translation) * orthographic.scale / old;
+    limits.limit_translation(&mut transform.translation);
+}
+
+fn despawn(mut commands: Commands) { commands.remove_resource::<TouchState>(); }
+
+pub fn touch(app: &mut App) {
+    app.add_systems(OnEnter(AppState::Playing), spawn)
+        .add_systems(
+            Update,
+            (press_event.run_if(not(resource_exists::<Replay>)), pinch_event)
+                .run_if(in_state(Pause::Running)),
+        )
+        .add_systems(OnEnter(AppState::Menu), despawn);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::camera::{CameraProjection, ComputedCameraValues, RenderTargetInfo};
+    use bevy::input::InputPlugin;
+    use bevy::input::touch::{TouchInput, TouchPhase};
+    use bevy::state::app::StatesPlugin;
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+    use crate::plugins::{Coordinates, MainCamera};
+
+    const TILE: f32 = 32.0;
+
+    #[derive(Resource, Default)]
+    struct Log(Vec<(&'static str, Coordinates)>);
+
+    struct Harness {
+        app: App,
+        window: Entity,
+        camera: Entity,
+    }
+
+    impl Harness {
+        fn new() -> Self {
+            let mut app = App::new();
+            app.add_plugins((
+                MinimalPlugins,
+                InputPlugin,
+                StatesPlugin,
+                WindowPlugin::default(),
+                touch,
+            ))
+            .init_state::<AppState>()
+            .add_sub_state::<Pause>()
+            .insert_resource(Preferences::default())
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)))
+            .init_resource::<Log>()
+            .add_observer(|e: On<LeftClicked>, mut log: ResMut<Log>| {
+                log.0.push(("left", e.coordinates));
+            })
+            .add_observer(|e: On<RightClicked>, mut log: ResMut<Log>| {
+                log.0.push(("right", e.coordinates));
+            });
+            let world = app.world_mut();
+            let board = world.spawn((Board::with_dimensions(4, 4), Controller::Any)).id();
+            let mut projection = OrthographicProjection {
+                scale: 1.0 / TILE,
+                ..OrthographicProjection::default_2d()
+            };
+            projection.update(640.0, 480.0);
+            let camera = Camera {
+                computed: ComputedCameraValues {
+                    clip_from_view: projection.get_clip_from_view(),
+                    target_info: Some(RenderTargetInfo {
+                        physical_size: UVec2::new(640, 480),
+                        scale_factor: 1.0,
+                    }),
+                    ..default()
+                },
+                ..default()
+            };
+            let transform = Transform::from_xyz(10.0, -7.5, 0.0);
+            let camera = world
+                .spawn((
+                    Camera2d,
+                    MainCamera,
+                    Views(board),
+                    CameraLimits::default(),
+                    camera,
+                    Projection::Orthographic(projection),
+                    transform,
+                    GlobalTransform::from(transform),
+                ))
+                .id();
+            let window = world.query_filtered::<Entity, With<Window>>().single(world).unwrap();
+            app.world_mut().resource_mut::<NextState<AppState>>().set(AppState::Playing);
+            app.update();
+            Self { app, window, camera }
+        }
+
+        fn touch(&mut self, phase: TouchPhase, x: f32, y: f32) {
+            let position = (Vec2::new(x, y) + 0.5) * TILE;
+            let window = self.window;
+            self.app.world_mut().write_message(TouchInput {
+                phase,
+                position,
+                window,
+                force: None,
+                id: 0,
+            });
+        }
+
+        fn frame(&mut self) -> Vec<(&'static str, Coordinates)> {
+            self.app.update();
+            std::mem::take(&mut self.app.world_mut().resource_mut::<Log>().0)
+        }
+    }
+
+    #[test]
+    fn tap_in_one_frame_reveals() {
+        let mut h = Harness::new();
+        h.touch(TouchPhase::Started, 2.0, 1.0);
+        h.touch(TouchPhase::Ended, 2.0, 1.0);
+        assert_eq!(h.frame(), [("left", Coordinates::new(2, 1))]);
+        h.touch(TouchPhase::Started, 1.0, 3.0);
+        assert_eq!(h.frame(), []);
+        h.touch(TouchPhase::Ended, 1.0, 3.0);
+        assert_eq!(h.frame(), [("left", Coordinates::new(1, 3))]);
+    }
+
+    #[test]
+    fn long_press_flags_once_without_revealing() {
+        let mut h = Harness::new();
+        h.touch(TouchPhase::Started, 3.0, 2.0);
+        let log: Vec<_> = (0..10).flat_map(|_| h.frame()).collect();
+        assert_eq!(log, [("right", Coordinates::new(3, 2))]);
+        h.touch(TouchPhase::Ended, 3.0, 2.0);
+        assert_eq!(h.frame(), []);
+    }
+
+    #[test]
+    fn drag_pans_without_clicking() {
+        let mut h = Harness::new();
+        let start = h.app.world().get::<Transform>(h.camera).unwrap().translation;
+        h.touch(TouchPhase::Started, 1.0, 1.0);
+        assert_eq!(h.frame(), []);
+        h.touch(TouchPhase::Moved, 2.0, 1.0);
+        assert_eq!(h.frame(), []);
+        h.touch(TouchPhase::Ended, 2.0, 1.0);
+        assert_eq!(h.frame(), []);
+        let end = h.app.world().get::<Transform>(h.camera).unwrap().translation;
+        assert_ne!(start, end);
+    }
+}
@@ -0,0 +1,7 @@
+mod board;
+mod layout;
+
+pub use board::{
+    Board, CHUNK, Click, Clicks, Coordinates, Difficulty, Face, Play, Size, TileState,
+};
+pub use layout::Layout;
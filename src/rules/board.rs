@@ -0,0 +1,923 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::mem;
+
+#[cfg(feature = "bevy")]
+use bevy::prelude::Component;
+use itertools::iproduct;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::rules::Layout;
+
+const OFFSETS: [(i32, i32); 8] =
+    [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+pub const CHUNK: i32 = 32;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Play {
+    Reveal(Vec<Coordinates>),
+    Chord,
+    Flag(Coordinates),
+    FlagChord(Vec<Coordinates>),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Click {
+    Left,
+    Right,
+    Chord,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub struct Clicks {
+    pub left: u32,
+    pub right: u32,
+    pub chords: u32,
+}
+
+impl Clicks {
+    pub fn count(&mut self, click: Click, play: &Play) {
+        match (click, play) {
+            (_, Play::Chord) => {},
+            (Click::Left, _) => self.left += 1,
+            (Click::Right, Play::Flag(_)) => self.right += 1,
+            (Click::Right | Click::Chord, _) => self.chords += 1,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default, Serialize, Deserialize)]
+pub struct Coordinates {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Coordinates {
+    #[must_use]
+    pub fn new(x: i32, y: i32) -> Self { Self { x, y } }
+
+    #[must_use]
+    pub fn chunk(self) -> (i32, i32) { (self.x.div_euclid(CHUNK), self.y.div_euclid(CHUNK)) }
+
+    #[must_use]
+    pub fn index(self) -> usize {
+        (self.y.rem_euclid(CHUNK) * CHUNK + self.x.rem_euclid(CHUNK)) as usize
+    }
+
+    #[must_use]
+    pub fn distance(self, other: Self) -> i32 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+}
+
+#[derive(Default, Copy, Clone)]
+struct Cell {
+    state: TileState,
+    mine: bool,
+    face: Face,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub struct Board {
+    pub mines: usize,
+    debug: bool,
+    pub difficulty: Difficulty,
+    pub size: Size,
+    dimensions: Option<(i32, i32)>,
+    seed: u64,
+    preset_seed: Option<u64>,
+    chunks: HashMap<(i32, i32), Box<[Cell]>>,
+    explored: Option<((i32, i32), (i32, i32))>,
+    dirty: HashSet<(i32, i32)>,
+    pressed: Vec<Coordinates>,
+    uncovered: usize,
+    flags: usize,
+    exploded: bool,
+    layout: Option<Layout>,
+}
+
+impl Board {
+    #[must_use]
+    pub fn new(size: Size, difficulty: Difficulty) -> Self {
+        let dimensions = size.dimensions().map(|(w, h)| (w as i32, h as i32));
+        let mines = dimensions.map_or(0, |(w, h)| difficulty.mine_count((w * h) as usize));
+        let debug = difficulty.is_debug();
+        Self {
+            mines,
+            debug,
+            difficulty,
+            size,
+            dimensions,
+            seed: 0,
+            preset_seed: None,
+            chunks: HashMap::new(),
+            explored: None,
+            dirty: HashSet::new(),
+            pressed: Vec::new(),
+            uncovered: 0,
+            flags: 0,
+            exploded: false,
+            layout: None,
+        }
+    }
+
+    #[must_use]
+    pub fn from_layout(layout: Layout, difficulty: Difficulty) -> Self {
+        Self {
+            mines: layout.mines().count(),
+            debug: false,
+            dimensions: Some((layout.width, layout.height)),
+            layout: Some(layout),
+            ..Self::new(Size::Custom, difficulty)
+        }
+    }
+
+    #[must_use]
+    pub fn with_seed(self, seed: u64) -> Self { Self { preset_seed: Some(seed), ..self } }
+
+    #[must_use]
+    pub fn preset_seed(&self) -> Option<u64> { self.preset_seed }
+
+    #[must_use]
+    pub fn mirror(size: Size, difficulty: Difficulty) -> Self {
+        let mut board = Self::new(size, difficulty);
+        board.fill([]);
+        board
+    }
+
+    #[must_use]
+    pub fn export(&self, state: bool) -> Option<Layout> {
+        let (width, height) = self.dimensions?;
+        let mut layout = Layout::new(width, height);
+        for c in self.coordinates() {
+            let cell = self.cell(c);
+            let state = match cell.state {
+                _ if !state => TileState::Covered,
+                TileState::Uncovered if cell.mine => TileState::Covered,
+                state => state,
+            };
+            layout.set(c, cell.mine, state);
+        }
+        Some(layout)
+    }
+
+    pub fn start(&mut self) { self.start_seeded(rand::random()); }
+
+    pub fn start_seeded(&mut self, seed: u64) {
+        if let Some(layout) = self.layout.take() {
+            self.restore(&layout);
+            self.layout = Some(layout);
+            return;
+        }
+        let Some((width, height)) = self.dimensions else {
+            self.start_endless(seed);
+            return;
+        };
+        let mines: Vec<_> = if self.debug {
+            iproduct!([1, 4, 7], [1, 4, 7])
+                .enumerate()
+                .flat_map(|(n, (x, y))| {
+                    OFFSETS
+                        .into_iter()
+                        .take(n)
+                        .map(move |(dx, dy)| Coordinates::new(x + dx, y + dy))
+                })
+                .collect()
+        } else {
+            let mut rand = ChaCha8Rng::seed_from_u64(seed);
+            rand::seq::index::sample(&mut rand, (width * height) as usize, self.mines)
+                .into_iter()
+                .map(|i| Coordinates::new(i as i32 % width, i as i32 / width))
+                .collect()
+        };
+        self.fill(mines);
+    }
+
+    #[cfg(test)]
+    #[must_use]
+    pub fn with_dimensions(width: i32, height: i32) -> Self {
+        let mut board =
+            Self { dimensions: Some((width, height)), ..Self::new(Size::Small, Difficulty::Easy) };
+        board.fill([]);
+        board
+    }
+
+    #[must_use]
+    pub fn is_endless(&self) -> bool { self.dimensions.is_none() }
+
+    #[must_use]
+    pub fn extent(&self) -> Option<(Coordinates, Coordinates)> {
+        match (self.dimensions, self.explored) {
+            (Some((width, height)), _) => {
+                Some((Coordinates::default(), Coordinates::new(width, height)))
+            },
+            (None, Some(((x0, y0), (x1, y1)))) => Some((
+                Coordinates::new(x0 * CHUNK, y0 * CHUNK),
+                Coordinates::new((x1 + 1) * CHUNK, (y1 + 1) * CHUNK),
+            )),
+            (None, None) => None,
+        }
+    }
+
+    #[must_use]
+    pub fn uncovered(&self) -> usize { self.uncovered }
+
+    #[must_use]
+    pub fn flags(&self) -> usize { self.flags }
+
+    #[must_use]
+    pub fn safe_cells(&self) -> Option<usize> {
+        self.dimensions.map(|(w, h)| (w * h) as usize - self.mines)
+    }
+
+    #[must_use]
+    pub fn remaining_mines(&self) -> isize { self.mines as isize - self.flags as isize }
+
+    #[must_use]
+    pub fn progress(&self) -> f32 {
+        self.safe_cells().map_or(0.0, |safe| self.uncovered as f32 / safe.max(1) as f32)
+    }
+
+    #[must_use]
+    pub fn is_cleared(&self) -> bool { self.safe_cells() == Some(self.uncovered) }
+
+    #[must_use]
+    pub fn is_exploded(&self) -> bool { self.exploded }
+
+    #[must_use]
+    pub fn face(&self, coordinates: Coordinates) -> Face { self.cell(coordinates).face }
+
+    #[must_use]
+    pub fn is_dirty(&self) -> bool { !self.dirty.is_empty() }
+
+    pub fn take_dirty(&mut self) -> HashSet<(i32, i32)> { mem::take(&mut self.dirty) }
+
+    #[must_use]
+    pub fn can_chord(&self, coordinates: Coordinates) -> bool {
+        let Some(cell) = self.get(coordinates) else { return false };
+        let TileValue::Neighbours(n) = self.value(coordinates) else { return false };
+        let flagged =
+            self.get_neighbours(coordinates).filter(|&c| self.cell(c).state == TileState::Flagged);
+        cell.state == TileState::Uncovered && flagged.count() == n as usize
+    }
+
+    pub fn chord(&mut self, coordinates: Coordinates) -> Vec<Coordinates> {
+        if !self.can_chord(coordinates) {
+            return Vec::new();
+        }
+        let neighbours: Vec<_> = self.get_neighbours(coordinates).collect();
+        self.reveal(neighbours)
+    }
+
+    pub fn toggle_flag(&mut self, coordinates: Coordinates) {
+        match self.cell(coordinates).state {
+            TileState::Covered => {
+                self.cell_mut(coordinates).state = TileState::Flagged;
+                self.set_face(coordinates, Face::Flagged);
+                self.flags += 1;
+            },
+            TileState::Flagged => {
+                self.cell_mut(coordinates).state = TileState::Covered;
+                self.set_face(coordinates, Face::Covered);
+                self.flags -= 1;
+            },
+            TileState::Uncovered => {},
+        }
+    }
+
+    pub fn left_click(&mut self, coordinates: Coordinates) -> Option<Play> {
+        let cell = self.get(coordinates).filter(|_| !self.exploded)?;
+        match (cell.state, self.value(coordinates)) {
+            (TileState::Uncovered, TileValue::Neighbours(_)) => Some(Play::Chord),
+            (TileState::Covered, _) => Some(Play::Reveal(self.reveal([coordinates]))),
+            (TileState::Uncovered | TileState::Flagged, _) => None,
+        }
+    }
+
+    pub fn chord_click(&mut self, coordinates: Coordinates) -> Option<Play> {
+        if self.exploded {
+            return None;
+        }
+        let revealed = self.chord(coordinates);
+        (!revealed.is_empty()).then_some(Play::Reveal(revealed))
+    }
+
+    pub fn right_click(&mut self, coordinates: Coordinates, flag_chord: bool) -> Option<Play> {
+        let cell = self.get(coordinates).filter(|_| !self.exploded)?;
+        if cell.state == TileState::Uncovered
+            && flag_chord
+            && let TileValue::Neighbours(n) = self.value(coordinates)
+        {
+            let covered: Vec<_> = self
+                .get_neighbours(coordinates)
+                .filter(|&c| self.cell(c).state != TileState::Uncovered)
+                .collect();
+            let flagged: Vec<_> = covered
+                .iter()
+                .copied()
+                .filter(|&c| self.cell(c).state == TileState::Covered)
+                .collect();
+            if covered.len() == n as usize && !flagged.is_empty() {
+                for &c in &flagged {
+                    self.toggle_flag(c);
+                }
+                return Some(Play::FlagChord(flagged));
+            }
+        }
+        if cell.state == TileState::Uncovered {
+            return None;
+        }
+        self.toggle_flag(coordinates);
+        Some(Play::Flag(coordinates))
+    }
+
+    pub fn claim(&mut self, coordinates: Coordinates) -> bool {
+        let Some(cell) = self.get(coordinates) else { return false };
+        if cell.state != TileState::Covered || !cell.mine {
+            return false;
+        }
+        self.toggle_flag(coordinates);
+        true
+    }
+
+    pub fn show(&mut self, coordinates: Coordinates, face: Face) {
+        let Some(cell) = self.get(coordinates) else { return };
+        match cell.state {
+            TileState::Uncovered if cell.face == Face::BombClicked => self.exploded = false,
+            TileState::Uncovered => self.uncovered -= 1,
+            TileState::Flagged => self.flags -= 1,
+            TileState::Covered => {},
+        }
+        let state = match face {
+            Face::Covered | Face::Pressed | Face::Bomb => TileState::Covered,
+            Face::Flagged | Face::WrongFlag => TileState::Flagged,
+            Face::Empty | Face::Neighbours(_) | Face::BombClicked => TileState::Uncovered,
+        };
+        match (state, face) {
+            (TileState::Uncovered, Face::BombClicked) => self.exploded = true,
+            (TileState::Uncovered, _) => self.uncovered += 1,
+            (TileState::Flagged, _) => self.flags += 1,
+            (TileState::Covered, _) => {},
+        }
+        self.cell_mut(coordinates).state = state;
+        self.set_face(coordinates, face);
+    }
+
+    #[must_use]
+    pub fn hint(&self) -> Option<Coordinates> {
+        let uncovered = |c| self.cell(c).state == TileState::Uncovered;
+        let mut safe = self.coordinates().filter(|&c| {
+            let cell = self.cell(c);
+            cell.state == TileState::Covered && !cell.mine
+        });
+        let frontier = safe.clone().find(|&c| self.get_neighbours(c).any(uncovered));
+        frontier.or_else(|| safe.next())
+    }
+
+    pub fn finish(&mut self, won: bool) {
+        if won {
+            self.reveal_covered_bombs(Face::Flagged);
+            return;
+        }
+        self.reveal_covered_bombs(Face::Bomb);
+        let wrong: Vec<_> = self
+            .coordinates()
+            .filter(|&c| self.cell(c).state == TileState::Flagged && !self.cell(c).mine)
+            .collect();
+        for c in wrong {
+            self.set_face(c, Face::WrongFlag);
+        }
+    }
+
+    fn reveal_covered_bombs(&mut self, face: Face) {
+        let bombs: Vec<_> = self
+            .coordinates()
+            .filter(|&c| self.cell(c).state == TileState::Covered && self.cell(c).mine)
+            .collect();
+        for c in bombs {
+            self.set_face(c, face);
+        }
+    }
+
+    pub fn touch(&mut self, coordinates: Coordinates) { self.dirty.insert(coordinates.chunk()); }
+
+    pub fn redraw(&mut self) { self.dirty.extend(self.chunks.keys().copied()); }
+
+    pub fn chunk_cells(&self, x: i32, y: i32) -> impl Iterator<Item = Coordinates> + Clone + use<> {
+        let (width, height) = self.dimensions.unwrap_or((i32::MAX, i32::MAX));
+        let xs = x * CHUNK..((x + 1) * CHUNK).min(width);
+        let ys = y * CHUNK..((y + 1) * CHUNK).min(height);
+        iproduct!(ys, xs).map(|(y, x)| Coordinates::new(x, y))
+    }
+
+    #[must_use]
+    pub fn contains(&self, coordinates: Coordinates) -> bool { self.get(coordinates).is_some() }
+
+    fn get(&self, coordinates: Coordinates) -> Option<Cell> {
+        if let Some((width, height)) = self.dimensions
+            && !((0..width).contains(&coordinates.x) && (0..height).contains(&coordinates.y))
+        {
+            return None;
+        }
+        self.chunks.get(&coordinates.chunk()).map(|chunk| chunk[coordinates.index()])
+    }
+
+    fn cell(&self, coordinates: Coordinates) -> Cell {
+        self.chunks[&coordinates.chunk()][coordinates.index()]
+    }
+
+    fn cell_mut(&mut self, coordinates: Coordinates) -> &mut Cell {
+        &mut self.chunks.get_mut(&coordinates.chunk()).unwrap()[coordinates.index()]
+    }
+
+    fn value(&self, coordinates: Coordinates) -> TileValue {
+        if self.cell(coordinates).mine {
+            return TileValue::Bomb;
+        }
+        match self.get_neighbours(coordinates).filter(|&c| self.cell(c).mine).count() {
+            0 => TileValue::Empty,
+            n => TileValue::Neighbours(n as u8),
+        }
+    }
+
+    pub fn coordinates(&self) -> impl Iterator<Item = Coordinates> + Clone {
+        let mut keys: Vec<_> = self.chunks.keys().copied().collect();
+        keys.sort_unstable_by_key(|&(x, y)| (y, x));
+        keys.into_iter().flat_map(|(x, y)| self.chunk_cells(x, y))
+    }
+
+    fn fill(&mut self, mines: impl IntoIterator<Item = Coordinates>) {
+        let (width, height) = self.dimensions.unwrap();
+        let (width, height) = ((width + CHUNK - 1) / CHUNK, (height + CHUNK - 1) / CHUNK);
+        let empty = vec![Cell::default(); (CHUNK * CHUNK) as usize].into_boxed_slice();
+        self.chunks = iproduct!(0..width, 0..height).map(|k| (k, empty.clone())).collect();
+        for c in mines {
+            self.cell_mut(c).mine = true;
+        }
+        self.dirty = self.chunks.keys().copied().collect();
+        self.pressed.clear();
+        self.uncovered = 0;
+        self.flags = 0;
+        self.exploded = false;
+    }
+
+    fn restore(&mut self, layout: &Layout) {
+        self.fill(layout.mines());
+        for (c, _, state) in layout.tiles() {
+            match state {
+                TileState::Covered => {},
+                TileState::Flagged => self.toggle_flag(c),
+                TileState::Uncovered => {
+                    self.uncover(c);
+                },
+            }
+        }
+    }
+
+    fn start_endless(&mut self, seed: u64) {
+        self.seed = seed;
+        self.chunks.clear();
+        self.explored = None;
+        self.pressed.clear();
+        self.uncovered = 0;
+        self.flags = 0;
+        self.exploded = false;
+        self.reveal([Coordinates::default()]);
+    }
+
+    fn explore(&mut self, coordinates: Coordinates) {
+        if !self.is_endless() {
+            return;
+        }
+        let (x, y) = coordinates.chunk();
+        let ((x0, y0), (x1, y1)) = self.explored.unwrap_or(((x, y), (x, y)));
+        let explored = ((x0.min(x - 1), y0.min(y - 1)), (x1.max(x + 1), y1.max(y + 1)));
+        if self.explored == Some(explored) {
+            return;
+        }
+        self.explored = Some(explored);
+        let ((x0, y0), (x1, y1)) = explored;
+        for (x, y) in iproduct!(x0..=x1, y0..=y1) {
+            if !self.chunks.contains_key(&(x, y)) {
+                self.generate(x, y);
+            }
+        }
+    }
+
+    fn generate(&mut self, x: i32, y: i32) {
+        let key = (x as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+            ^ (y as u64).wrapping_mul(0xc2b2_ae3d_27d4_eb4f);
+        let mut rand = ChaCha8Rng::seed_from_u64(self.seed ^ key);
+        let size = (CHUNK * CHUNK) as usize;
+        let mut cells = vec![Cell::default(); size].into_boxed_slice();
+        for i in rand::seq::index::sample(&mut rand, size, self.difficulty.mine_count(size)) {
+            let c = Coordinates::new(x * CHUNK + i as i32 % CHUNK, y * CHUNK + i as i32 / CHUNK);
+            cells[i].mine = c.x.abs() > 1 || c.y.abs() > 1;
+        }
+        self.chunks.insert((x, y), cells);
+        self.dirty.insert((x, y));
+    }
+
+    pub fn reveal(&mut self, start: impl IntoIterator<Item = Coordinates>) -> Vec<Coordinates> {
+        let mut queue: VecDeque<_> = start.into_iter().collect();
+        let mut revealed = Vec::new();
+        while let Some(coordinates) = queue.pop_front() {
+            self.explore(coordinates);
+            if self.cell(coordinates).state != TileState::Covered {
+                continue;
+            }
+            let value = self.uncover(coordinates);
+            revealed.push(coordinates);
+            if value == TileValue::Empty {
+                queue.extend(
+                    self.get_neighbours(coordinates)
+                        .filter(|&c| self.cell(c).state == TileState::Covered),
+                );
+            }
+        }
+        revealed
+    }
+
+    fn uncover(&mut self, coordinates: Coordinates) -> TileValue {
+        self.cell_mut(coordinates).state = TileState::Uncovered;
+        let value = self.value(coordinates);
+        self.set_face(coordinates, value.face());
+        if value == TileValue::Bomb {
+            self.exploded = true;
+        } else {
+            self.uncovered += 1;
+        }
+        value
+    }
+
+    fn set_face(&mut self, coordinates: Coordinates, face: Face) {
+        let cell = self.cell_mut(coordinates);
+        if cell.face != face {
+            cell.face = face;
+            self.dirty.insert(coordinates.chunk());
+        }
+    }
+
+    #[must_use]
+    pub fn pressed(&self) -> &[Coordinates] { &self.pressed }
+
+    pub fn set_pressed(&mut self, pressed: Vec<Coordinates>) {
+        for c in mem::take(&mut self.pressed) {
+            if !pressed.contains(&c) && self.cell(c).state == TileState::Covered {
+                self.set_face(c, Face::Covered);
+            }
+        }
+        for &c in &pressed {
+            if self.cell(c).state == TileState::Covered {
+                self.set_face(c, Face::Pressed);
+            }
+        }
+        self.pressed = pressed;
+    }
+
+    pub fn get_neighbours(&self, coordinates: Coordinates) -> impl Iterator<Item = Coordinates> {
+        OFFSETS.into_iter().filter_map(move |(dx, dy)| {
+            Some(Coordinates::new(coordinates.x + dx, coordinates.y + dy))
+                .filter(|&c| self.contains(c))
+        })
+    }
+}
+
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum Face {
+    #[default]
+    Covered,
+    Pressed,
+    Flagged,
+    Empty,
+    Neighbours(u8),
+    Bomb,
+    BombClicked,
+    WrongFlag,
+}
+
+impl Face {
+    pub const ALL: [Self; 15] = [
+        Self::Covered,
+        Self::Pressed,
+        Self::Flagged,
+        Self::Empty,
+        Self::Neighbours(1),
+        Self::Neighbours(2),
+        Self::Neighbours(3),
+        Self::Neighbours(4),
+        Self::Neighbours(5),
+        Self::Neighbours(6),
+        Self::Neighbours(7),
+        Self::Neighbours(8),
+        Self::Bomb,
+        Self::BombClicked,
+        Self::WrongFlag,
+    ];
+
+    #[must_use]
+    pub fn index(self) -> usize {
+        match self {
+            Self::Covered => 0,
+            Self::Pressed => 1,
+            Self::Flagged => 2,
+            Self::Empty => 3,
+            Self::Neighbours(n) => 3 + n as usize,
+            Self::Bomb => 12,
+            Self::BombClicked => 13,
+            Self::WrongFlag => 14,
+        }
+    }
+}
+
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum TileState {
+    #[default]
+    Covered,
+    Flagged,
+    Uncovered,
+}
+
+#[derive(Default, PartialEq, Eq, Clone, Copy)]
+enum TileValue {
+    #[default]
+    Empty,
+    Neighbours(u8),
+    Bomb,
+}
+
+impl TileValue {
+    fn face(self) -> Face {
+        match self {
+            Self::Empty => Face::Empty,
+            Self::Neighbours(n) => Face::Neighbours(n),
+            Self::Bomb => Face::BombClicked,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub enum Size {
+    Small,
+    Medium,
+    Big,
+    Huge,
+    Giant,
+    Colossal,
+    Endless,
+    Custom,
+}
+
+impl Size {
+    #[must_use]
+    pub fn dimensions(self) -> Option<(usize, usize)> {
+        match self {
+            Size::Small => Some((16, 9)),
+            Size::Medium => Some((32, 18)),
+            Size::Big => Some((48, 27)),
+            Size::Huge => Some((64, 36)),
+            Size::Giant => Some((256, 144)),
+            Size::Colossal => Some((1000, 1000)),
+            Size::Endless | Size::Custom => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Extreme,
+    Debug,
+}
+
+impl Difficulty {
+    #[must_use]
+    pub fn mine_count(self, cells: usize) -> usize {
+        match self {
+            Difficulty::Easy => cells / 10,
+            Difficulty::Medium => cells * 3 / 20,
+            Difficulty::Hard => cells / 5,
+            Difficulty::Extreme => cells / 4,
+            Difficulty::Debug => 36,
+        }
+    }
+
+    #[must_use]
+    pub fn is_debug(self) -> bool { self == Self::Debug }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reveal_stops_at_numbers() {
+        let mut board = Board::with_dimensions(5, 1);
+        board.fill([Coordinates::new(4, 0)]);
+        let revealed = board.reveal([Coordinates::new(0, 0)]);
+        assert_eq!(revealed.len(), 4);
+        assert!(board.cell(Coordinates::new(4, 0)).state == TileState::Covered);
+    }
+
+    #[test]
+    fn counters_follow_reveals_and_flags() {
+        let mut board = Board::with_dimensions(5, 1);
+        board.mines = 1;
+        board.fill([Coordinates::new(4, 0)]);
+        board.toggle_flag(Coordinates::new(4, 0));
+        board.toggle_flag(Coordinates::new(2, 0));
+        board.toggle_flag(Coordinates::new(2, 0));
+        assert_eq!((board.flags(), board.remaining_mines()), (1, 0));
+        board.reveal([Coordinates::new(3, 0)]);
+        assert_eq!(board.uncovered(), 1);
+        assert!(!board.is_cleared());
+        board.reveal([Coordinates::new(0, 0)]);
+        assert_eq!(board.uncovered(), 4);
+        assert!(board.is_cleared());
+        assert!((board.progress() - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn chords_explode_on_misplaced_flags() {
+        let mine = Coordinates::new(0, 0);
+        let (number, safe) = (Coordinates::new(1, 0), Coordinates::new(2, 0));
+        let mut board = Board::with_dimensions(3, 1);
+        board.mines = 1;
+        board.fill([mine]);
+        board.reveal([number]);
+        assert!(board.chord(number).is_empty());
+        assert_eq!(board.hint(), Some(safe));
+        assert_eq!(Board::with_dimensions(64, 64).hint(), Some(Coordinates::new(0, 0)));
+        board.toggle_flag(mine);
+        assert_eq!(board.chord(number), [safe]);
+        assert!(board.is_cleared() && !board.is_exploded());
+        let mut board = Board::with_dimensions(3, 1);
+        board.mines = 1;
+        board.fill([mine]);
+        board.reveal([number]);
+        board.toggle_flag(safe);
+        assert_eq!(board.chord(number), [mine]);
+        assert!(board.is_exploded());
+        board.finish(false);
+        assert_eq!(board.face(safe), Face::WrongFlag);
+    }
+
+    #[test]
+    fn clicks_follow_the_classic_rules() {
+        let mine = Coordinates::new(0, 0);
+        let (number, safe) = (Coordinates::new(1, 0), Coordinates::new(2, 0));
+        let mut board = Board::with_dimensions(3, 1);
+        board.mines = 1;
+        board.fill([mine]);
+        assert_eq!(board.left_click(number), Some(Play::Reveal(vec![number])));
+        assert_eq!(board.left_click(number), Some(Play::Chord));
+        assert_eq!(board.right_click(safe, false), Some(Play::Flag(safe)));
+        assert_eq!(board.left_click(safe), None);
+        assert_eq!(board.right_click(safe, false), Some(Play::Flag(safe)));
+        assert_eq!(board.left_click(safe), Some(Play::Reveal(vec![safe])));
+        assert_eq!(board.right_click(number, true), Some(Play::FlagChord(vec![mine])));
+        assert_eq!(board.chord_click(number), None);
+        assert_eq!(board.right_click(Coordinates::new(3, 0), false), None);
+        assert!(board.is_cleared() && board.flags() == 1);
+    }
+
+    #[test]
+    fn only_effective_clicks_are_counted() {
+        let mine = Coordinates::new(0, 0);
+        let (number, safe) = (Coordinates::new(1, 0), Coordinates::new(2, 0));
+        let mut board = Board::with_dimensions(3, 1);
+        board.mines = 1;
+        board.fill([mine]);
+        board.reveal([number]);
+        let mut clicks = Clicks::default();
+        for (click, coordinates) in [
+            (Click::Chord, safe),
+            (Click::Chord, number),
+            (Click::Right, number),
+            (Click::Right, mine),
+            (Click::Right, number),
+            (Click::Chord, number),
+        ] {
+            let play = match click {
+                Click::Left => board.left_click(coordinates),
+                Click::Right => board.right_click(coordinates, false),
+                Click::Chord => board.chord_click(coordinates),
+            };
+            if let Some(play) = play {
+                clicks.count(click, &play);
+            }
+        }
+        assert_eq!(clicks, Clicks { left: 0, right: 1, chords: 1 });
+        assert!(board.is_cleared());
+        assert_eq!(board.right_click(number, true), None);
+    }
+
+    #[test]
+    fn endless_chunks_are_generated_from_the_seed() {
+        let mut board = Board::new(Size::Endless, Difficulty::Medium);
+        board.start_endless(7);
+        assert!(board.uncovered() > 0);
+        let (_, max) = board.extent().unwrap();
+        let beyond = Coordinates::new(max.x, 0);
+        assert!(board.get(beyond).is_none());
+        board.explore(Coordinates::new(beyond.x - 1, 0));
+        assert_eq!(board.extent().unwrap().1.x, max.x + CHUNK);
+        assert!(board.get(beyond).is_some());
+        let mut other = Board::new(Size::Endless, Difficulty::Medium);
+        other.start_endless(7);
+        other.explore(Coordinates::new(beyond.x - 1, 0));
+        assert_eq!(board.explored, other.explored);
+        for key in board.chunks.keys() {
+            let mines = |b: &Board| b.chunks[key].iter().map(|c| c.mine).collect::<Vec<_>>();
+            assert_eq!(mines(&board), mines(&other));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "bevy"))]
+mod flood {
+    use std::thread;
+
+    use bevy::prelude::{Commands, Event, On, Single, World};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    #[derive(Event)]
+    struct Flood {
+        coordinates: Coordinates,
+    }
+
+    fn cascade(flood: On<Flood>, mut commands: Commands, mut board: Single<&mut Board>) {
+        let coordinates = flood.coordinates;
+        if board.cell(coordinates).state != TileState::Covered {
+            return;
+        }
+        board.cell_mut(coordinates).state = TileState::Uncovered;
+        let value = board.value(coordinates);
+        board.set_face(coordinates, value.face());
+        if value == TileValue::Empty {
+            for coordinates in board.get_neighbours(coordinates) {
+                commands.trigger(Flood { coordinates });
+            }
+        }
+    }
+
+    fn huge_board(mines: usize, seed: u64) -> Board {
+        let (width, height) = Size::Huge.dimensions().unwrap();
+        let mut board = Board::with_dimensions(width as i32, height as i32);
+        let mut rand = ChaCha8Rng::seed_from_u64(seed);
+        let sample = rand::seq::index::sample(&mut rand, width * height, mines);
+        board
+            .fill(sample.into_iter().map(|i| Coordinates::new((i % width) as _, (i / width) as _)));
+        board
+    }
+
+    fn cells(board: &Board) -> impl Iterator<Item = Coordinates> {
+        let (_, max) = board.extent().unwrap();
+        iproduct!(0..max.y, 0..max.x).map(|(y, x)| Coordinates::new(x, y))
+    }
+
+    fn first_empty(board: &Board) -> Coordinates {
+        cells(board).find(|&c| board.value(c) == TileValue::Empty).unwrap()
+    }
+
+    fn uncovered(board: &Board) -> Vec<bool> {
+        cells(board).map(|c| board.cell(c).state == TileState::Uncovered).collect()
+    }
+
+    fn run_cascade(board: Board, start: Coordinates) -> Board {
+        let run = move || {
+            let mut world = World::new();
+            world.add_observer(cascade);
+            let entity = world.spawn(board).id();
+            world.flush();
+            world.trigger(Flood { coordinates: start });
+            world.flush();
+            world.entity_mut(entity).take::<Board>().unwrap()
+        };
+        thread::Builder::new().stack_size(256 << 20).spawn(run).unwrap().join().unwrap()
+    }
+
+    #[test]
+    fn batched_flood_matches_cascade() {
+        let mines = Difficulty::Easy.mine_count(64 * 36);
+        for seed in 0..8 {
+            let mut batched = huge_board(mines, seed);
+            let start = first_empty(&batched);
+            let cascaded = run_cascade(huge_board(mines, seed), start);
+            batched.reveal([start]);
+            assert_eq!(uncovered(&cascaded), uncovered(&batched));
+            assert_eq!(cascaded.dirty, batched.dirty);
+        }
+    }
+}
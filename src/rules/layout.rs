@@ -0,0 +1,177 @@
+use std::collections::HashSet;
+use std::fmt;
+
+#[cfg(feature = "bevy")]
+use bevy::prelude::Resource;
+
+use crate::rules::{Coordinates, TileState};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "bevy", derive(Resource))]
+pub struct Layout {
+    pub width: i32,
+    pub height: i32,
+    cells: Vec<(bool, TileState)>,
+}
+
+impl Layout {
+    #[must_use]
+    pub fn new(width: i32, height: i32) -> Self {
+        Self { width, height, cells: vec![(false, TileState::Covered); (width * height) as usize] }
+    }
+
+    pub fn set(&mut self, coordinates: Coordinates, mine: bool, state: TileState) {
+        let index = (coordinates.y * self.width + coordinates.x) as usize;
+        self.cells[index] = (mine, state);
+    }
+
+    pub fn tiles(&self) -> impl Iterator<Item = (Coordinates, bool, TileState)> + '_ {
+        self.cells.iter().enumerate().map(|(i, &(mine, state))| {
+            let i = i as i32;
+            (Coordinates::new(i % self.width, i / self.width), mine, state)
+        })
+    }
+
+    pub fn mines(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        self.tiles().filter(|&(_, mine, _)| mine).map(|(c, ..)| c)
+    }
+
+    fn is_mine(&self, coordinates: Coordinates) -> bool {
+        (0..self.width).contains(&coordinates.x)
+            && (0..self.height).contains(&coordinates.y)
+            && self.cells[(coordinates.y * self.width + coordinates.x) as usize].0
+    }
+
+    fn around(&self, coordinates: Coordinates) -> impl Iterator<Item = Coordinates> + use<> {
+        let (x, y, width, height) = (coordinates.x, coordinates.y, self.width, self.height);
+        (y - 1..=y + 1)
+            .flat_map(move |y| (x - 1..=x + 1).map(move |x| Coordinates::new(x, y)))
+            .filter(move |c| (0..width).contains(&c.x) && (0..height).contains(&c.y))
+    }
+
+    fn neighbours(&self, coordinates: Coordinates) -> usize {
+        self.around(coordinates).filter(|&c| self.is_mine(c)).count()
+    }
+
+    #[must_use]
+    pub fn bbbv(&self) -> usize {
+        let opening = |c| !self.is_mine(c) && self.neighbours(c) == 0;
+        let mut cleared = HashSet::new();
+        let mut clicks = 0;
+        for (start, ..) in self.tiles() {
+            if !opening(start) || cleared.contains(&start) {
+                continue;
+            }
+            clicks += 1;
+            let mut queue = vec![start];
+            cleared.insert(start);
+            while let Some(c) = queue.pop() {
+                for next in self.around(c) {
+                    if cleared.insert(next) && opening(next) {
+                        queue.push(next);
+                    }
+                }
+            }
+        }
+        clicks + self.tiles().filter(|&(c, mine, _)| !mine && !cleared.contains(&c)).count()
+    }
+
+    #[allow(clippy::missing_errors_doc)]
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let rows: Vec<_> = text.lines().map(str::trim_end).filter(|l| !l.is_empty()).collect();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        if width == 0 {
+            return Err(String::from("the board is empty"));
+        }
+        let mut layout = Self::new(width as i32, rows.len() as i32);
+        let mut numbers = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            if row.chars().count() != width {
+                return Err(format!("row {} has a different width", y + 1));
+            }
+            for (x, symbol) in row.chars().enumerate() {
+                let (mine, state) = match symbol {
+                    '.' => (false, TileState::Covered),
+                    '*' => (true, TileState::Covered),
+                    'f' => (false, TileState::Flagged),
+                    'F' => (true, TileState::Flagged),
+                    '0'..='8' => {
+                        numbers.push((x, y, symbol));
+                        (false, TileState::Uncovered)
+                    },
+                    _ => {
+                        return Err(format!(
+                            "unknown symbol {symbol:?} at row {} column {}",
+                            y + 1,
+                            x + 1
+                        ));
+                    },
+                };
+                layout.set(Coordinates::new(x as i32, y as i32), mine, state);
+            }
+        }
+        for (x, y, symbol) in numbers {
+            let mines = layout.neighbours(Coordinates::new(x as i32, y as i32));
+            if symbol.to_digit(10) != Some(mines as u32) {
+                return Err(format!(
+                    "{symbol} at row {} column {} does not match its {mines} neighbouring mines",
+                    y + 1,
+                    x + 1
+                ));
+            }
+        }
+        Ok(layout)
+    }
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (coordinates, mine, state) in self.tiles() {
+            let symbol = match (mine, state) {
+                (false, TileState::Covered) => '.',
+                (true, TileState::Covered | TileState::Uncovered) => '*',
+                (false, TileState::Flagged) => 'f',
+                (true, TileState::Flagged) => 'F',
+                (false, TileState::Uncovered) => {
+                    char::from(b'0' + self.neighbours(coordinates) as u8)
+                },
+            };
+            write!(f, "{symbol}")?;
+            if coordinates.x == self.width - 1 {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Board, Difficulty, Face};
+
+    #[test]
+    fn layouts_round_trip_through_the_board() {
+        let text = "*..\n.2f\nF..\n";
+        let layout = Layout::parse(text).unwrap();
+        assert_eq!(layout.mines().count(), 2);
+        assert_eq!(layout.bbbv(), 2);
+        assert_eq!(Layout::parse("*...\n....\n..*.\n").unwrap().bbbv(), 3);
+        let mut board = Board::from_layout(layout.clone(), Difficulty::Easy);
+        board.start();
+        assert_eq!(board.face(Coordinates::new(1, 1)), Face::Neighbours(2));
+        assert_eq!((board.flags(), board.uncovered()), (2, 1));
+        assert_eq!(board.export(true).unwrap().to_string(), text);
+        assert_eq!(board.export(false).unwrap().to_string(), "*..\n...\n*..\n");
+        assert_eq!(board.export(true), Some(layout));
+    }
+
+    #[test]
+    fn malformed_layouts_are_rejected() {
+        assert!(Layout::parse("\n\n").is_err());
+        assert!(Layout::parse("*..\n..\n").is_err());
+        assert!(Layout::parse("*.x\n").is_err());
+        assert!(Layout::parse("*1\n11\n").is_ok());
+        assert!(Layout::parse("*2\n..\n").is_err());
+    }
+}
@@ -1,5 +1,5 @@
-mod nord;
+mod persistent;
 mod projection_extensions;
 
-pub use nord::Nord;
+pub use persistent::{Persistent, config_path};
 pub use projection_extensions::ProjectionExt;
@@ -0,0 +1,38 @@
+use std::path::PathBuf;
+use std::{fs, io};
+
+use bevy::log::warn;
+use ron::ser::PrettyConfig;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+pub fn config_path(file: &str) -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("minesweeper").join(file))
+}
+
+pub trait Persistent: Serialize + DeserializeOwned + Default {
+    const FILE: &'static str;
+
+    fn load() -> Self {
+        let Some(path) = config_path(Self::FILE) else { return Self::default() };
+        let Ok(text) = fs::read_to_string(&path) else { return Self::default() };
+        ron::from_str(&text).unwrap_or_else(|e| {
+            warn!("Failed to parse {}: {e}", path.display());
+            Self::default()
+        })
+    }
+
+    fn save(&self) {
+        let Some(path) = config_path(Self::FILE) else { return };
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| {
+                ron::ser::to_string_pretty(self, PrettyConfig::default()).map_err(io::Error::other)
+            })
+            .and_then(|text| fs::write(&path, text));
+        if let Err(e) = result {
+            warn!("Failed to save {}: {e}", path.display());
+        }
+    }
+}
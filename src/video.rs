@@ -0,0 +1,443 @@
+use std::collections::HashMap;
+use std::{fmt, mem};
+
+use crate::rules::{Board, Click, Clicks, Coordinates, Difficulty, Layout, Play, TileState};
+
+pub const SQUARE: i32 = 16;
+const TIME_TOLERANCE: f32 = 0.1;
+const DIFFICULTIES: [Difficulty; 4] =
+    [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard, Difficulty::Extreme];
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MouseEvent {
+    Move,
+    LeftPress,
+    LeftRelease,
+    RightPress,
+    RightRelease,
+    MiddlePress,
+    MiddleRelease,
+}
+
+impl MouseEvent {
+    const ALL: [Self; 7] = [
+        Self::Move,
+        Self::LeftPress,
+        Self::LeftRelease,
+        Self::RightPress,
+        Self::RightRelease,
+        Self::MiddlePress,
+        Self::MiddleRelease,
+    ];
+
+    fn code(self) -> &'static str {
+        match self {
+            MouseEvent::Move => "mv",
+            MouseEvent::LeftPress => "lc",
+            MouseEvent::LeftRelease => "lr",
+            MouseEvent::RightPress => "rc",
+            MouseEvent::RightRelease => "rr",
+            MouseEvent::MiddlePress => "mc",
+            MouseEvent::MiddleRelease => "mr",
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct VideoEvent {
+    pub time: f32,
+    pub mouse: MouseEvent,
+    pub x: i32,
+    pub y: i32,
+}
+
+impl VideoEvent {
+    #[must_use]
+    pub fn coordinates(self) -> Coordinates {
+        Coordinates::new(self.x.div_euclid(SQUARE), self.y.div_euclid(SQUARE))
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Video {
+    pub layout: Layout,
+    pub player: String,
+    pub timestamp: String,
+    pub time: f32,
+    pub won: bool,
+    pub bbbv: Option<usize>,
+    pub clicks: Option<Clicks>,
+    pub flag_chord: bool,
+    pub events: Vec<VideoEvent>,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Outcome {
+    pub won: Option<bool>,
+    pub time: f32,
+    pub bbbv: usize,
+    pub clicks: Clicks,
+}
+
+impl Video {
+    /// Built-in board sizes never match the classic levels, so only boards imported with
+    /// `--board` can be saved as anything other than `Custom`.
+    fn level(&self) -> &'static str {
+        match (self.layout.width, self.layout.height, self.layout.mines().count()) {
+            (8 | 9, 8 | 9, 10) => "Beginner",
+            (16, 16, 40) => "Intermediate",
+            (30, 16, 99) => "Expert",
+            _ => "Custom",
+        }
+    }
+
+    #[allow(clippy::missing_errors_doc)]
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().map(str::trim);
+        let mut headers = HashMap::new();
+        for line in lines.by_ref().take_while(|&line| line != "Board:") {
+            if let Some((key, value)) = line.split_once(':') {
+                headers.insert(key.trim().to_lowercase(), value.trim().to_owned());
+            }
+        }
+        let rows: Vec<_> = lines
+            .by_ref()
+            .take_while(|&line| line != "Events:")
+            .filter(|l| !l.is_empty())
+            .collect();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        if width == 0 {
+            return Err(String::from("the video has no board"));
+        }
+        let mut layout = Layout::new(width as i32, rows.len() as i32);
+        for (y, row) in rows.iter().enumerate() {
+            if row.chars().count() != width {
+                return Err(format!("board row {} has a different width", y + 1));
+            }
+            for (x, symbol) in row.chars().enumerate() {
+                layout.set(Coordinates::new(x as i32, y as i32), symbol == '*', TileState::Covered);
+            }
+        }
+        let mut events = Vec::new();
+        let mut won = false;
+        for line in lines {
+            let mut fields = line.split_whitespace();
+            let (Some(time), Some(name)) = (fields.next(), fields.next()) else { continue };
+            let Ok(time) = time.parse() else { continue };
+            won |= name == "won";
+            let Some(mouse) = MouseEvent::ALL.into_iter().find(|m| m.code() == name) else {
+                continue;
+            };
+            let mut position = fields.map(str::parse);
+            let (Some(Ok(x)), Some(Ok(y))) = (position.next(), position.next()) else {
+                return Err(format!("malformed event {line:?}"));
+            };
+            events.push(VideoEvent { time, mouse, x, y });
+        }
+        let header = |key: &str| headers.get(key).cloned().unwrap_or_default();
+        let time = header("time").parse().unwrap_or_else(|_| events.last().map_or(0.0, |e| e.time));
+        let clicks = match (header("lclicks").parse(), header("rclicks").parse()) {
+            (Ok(left), Ok(right)) => {
+                header("dclicks").parse().ok().map(|chords| Clicks { left, right, chords })
+            },
+            _ => None,
+        };
+        Ok(Self {
+            layout,
+            player: header("player"),
+            timestamp: header("timestamp"),
+            time,
+            won,
+            bbbv: header("bbbv").parse().ok(),
+            clicks,
+            flag_chord: header("flagchord") == "On",
+            events,
+        })
+    }
+
+    #[allow(clippy::missing_errors_doc)]
+    pub fn simulate(&self) -> Result<Outcome, String> {
+        let mut board = playback_board(self.layout.clone());
+        board.start();
+        let mut clicks = Clicks::default();
+        let outcome = |won, time, clicks| Outcome { won, time, bbbv: self.layout.bbbv(), clicks };
+        let mut buttons = Buttons::default();
+        let mut previous = 0.0;
+        for event in &self.events {
+            if event.time < previous {
+                return Err(format!("the event at {:.3} goes back in time", event.time));
+            }
+            previous = event.time;
+            let coordinates = event.coordinates();
+            let Some(click) = buttons.apply(event.mouse) else { continue };
+            let play = match click {
+                Click::Left => board.left_click(coordinates),
+                Click::Right => board.right_click(coordinates, self.flag_chord),
+                Click::Chord => None,
+            };
+            if let Some(play) = &play {
+                clicks.count(click, play);
+            }
+            if (click == Click::Chord || play == Some(Play::Chord))
+                && let Some(play) = board.chord_click(coordinates)
+            {
+                clicks.count(Click::Chord, &play);
+            }
+            if board.is_exploded() || board.is_cleared() {
+                return Ok(outcome(Some(board.is_cleared()), event.time, clicks));
+            }
+        }
+        Ok(outcome(None, previous, clicks))
+    }
+
+    #[allow(clippy::missing_errors_doc)]
+    pub fn verify(&self) -> Result<Outcome, Vec<String>> {
+        let outcome = self.simulate().map_err(|e| vec![e])?;
+        let mut errors = Vec::new();
+        if outcome.won != Some(self.won) {
+            let claimed = if self.won { "won" } else { "lost" };
+            let actual = match outcome.won {
+                Some(true) => "won",
+                Some(false) => "lost",
+                None => "unfinished",
+            };
+            errors.push(format!("the game was claimed {claimed} but the replay is {actual}"));
+        }
+        if (outcome.time - self.time).abs() > TIME_TOLERANCE {
+            errors.push(format!(
+                "the time was claimed {:.3} but the replay ends at {:.3}",
+                self.time, outcome.time
+            ));
+        }
+        if let Some(bbbv) = self.bbbv
+            && bbbv != outcome.bbbv
+        {
+            errors.push(format!("the 3BV was claimed {bbbv} but the board has {}", outcome.bbbv));
+        }
+        if let Some(clicks) = self.clicks
+            && clicks != outcome.clicks
+        {
+            let count = |c: Clicks| format!("{}/{}/{}", c.left, c.right, c.chords);
+            errors.push(format!(
+                "the clicks were claimed {} but the replay makes {}",
+                count(clicks),
+                count(outcome.clicks)
+            ));
+        }
+        if errors.is_empty() { Ok(outcome) } else { Err(errors) }
+    }
+}
+
+impl fmt::Display for Video {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let layout = &self.layout;
+        writeln!(f, "RawVF_Version: Rev5")?;
+        writeln!(f, "Program: Minesweeper")?;
+        writeln!(f, "Version: {}", env!("CARGO_PKG_VERSION"))?;
+        writeln!(f, "Player: {}", self.player)?;
+        writeln!(f, "Timestamp: {}", self.timestamp)?;
+        writeln!(f, "Level: {}", self.level())?;
+        writeln!(f, "Width: {}", layout.width)?;
+        writeln!(f, "Height: {}", layout.height)?;
+        writeln!(f, "Mines: {}", layout.mines().count())?;
+        writeln!(f, "Marks: Off")?;
+        writeln!(f, "Mode: Classic")?;
+        writeln!(f, "Time: {:.3}", self.time)?;
+        writeln!(f, "BBBV: {}", self.bbbv.unwrap_or_else(|| layout.bbbv()))?;
+        if let Some(clicks) = self.clicks {
+            writeln!(f, "LClicks: {}", clicks.left)?;
+            writeln!(f, "RClicks: {}", clicks.right)?;
+            writeln!(f, "DClicks: {}", clicks.chords)?;
+        }
+        writeln!(f, "FlagChord: {}", if self.flag_chord { "On" } else { "Off" })?;
+        writeln!(f, "Board:")?;
+        for (c, mine, _) in layout.tiles() {
+            write!(f, "{}", if mine { '*' } else { '0' })?;
+            if c.x == layout.width - 1 {
+                writeln!(f)?;
+            }
+        }
+        writeln!(f, "Events:")?;
+        writeln!(f, "0.000 start")?;
+        for event in &self.events {
+            let c = event.coordinates();
+            let (time, code, x, y) = (event.time, event.mouse.code(), event.x, event.y);
+            writeln!(f, "{time:.3} {code} {x} {y} ({} {})", c.x + 1, c.y + 1)?;
+        }
+        writeln!(f, "{:.3} {}", self.time, if self.won { "won" } else { "blast" })
+    }
+}
+
+#[must_use]
+pub fn playback_board(layout: Layout) -> Board {
+    let (cells, mines) = ((layout.width * layout.height) as usize, layout.mines().count());
+    let difficulty = DIFFICULTIES.into_iter().min_by_key(|d| d.mine_count(cells).abs_diff(mines));
+    Board::from_layout(layout, difficulty.unwrap_or(Difficulty::Easy))
+}
+
+#[must_use]
+pub fn timestamp(seconds: u64) -> String {
+    let (days, rest) = ((seconds / 86_400) as i64, seconds % 86_400);
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    let (hours, minutes, seconds) = (rest / 3600, rest / 60 % 60, rest % 60);
+    format!("{year}-{month:02}-{day:02} {hours:02}:{minutes:02}:{seconds:02}")
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Default)]
+enum Chord {
+    #[default]
+    Off,
+    Held,
+    Done,
+}
+
+#[derive(Default)]
+pub struct Buttons {
+    left: bool,
+    right: bool,
+    chord: Chord,
+}
+
+impl Buttons {
+    fn release_chord(&mut self) -> Option<Click> {
+        (mem::replace(&mut self.chord, Chord::Done) == Chord::Held).then_some(Click::Chord)
+    }
+
+    pub fn apply(&mut self, mouse: MouseEvent) -> Option<Click> {
+        let click = match mouse {
+            MouseEvent::Move | MouseEvent::MiddlePress => None,
+            MouseEvent::MiddleRelease => Some(Click::Chord),
+            MouseEvent::LeftPress => {
+                self.left = true;
+                if self.right && self.chord == Chord::Off {
+                    self.chord = Chord::Held;
+                }
+                None
+            },
+            MouseEvent::RightPress => {
+                self.right = true;
+                if self.left {
+                    if self.chord == Chord::Off {
+                        self.chord = Chord::Held;
+                    }
+                    None
+                } else {
+                    Some(Click::Right)
+                }
+            },
+            MouseEvent::LeftRelease => {
+                self.left = false;
+                if self.chord == Chord::Off { Some(Click::Left) } else { self.release_chord() }
+            },
+            MouseEvent::RightRelease => {
+                self.right = false;
+                if self.chord == Chord::Off { None } else { self.release_chord() }
+            },
+        };
+        if !self.left && !self.right {
+            self.chord = Chord::Off;
+        }
+        click
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MouseEvent::{
+        LeftPress, LeftRelease, MiddlePress, MiddleRelease, Move, RightPress, RightRelease,
+    };
+    use super::*;
+
+    #[test]
+    fn buttons_follow_classic_click_rules() {
+        let mut buttons = Buttons::default();
+        let mut clicks = |mice: &[MouseEvent]| -> Vec<_> {
+            mice.iter().filter_map(|&m| buttons.apply(m)).collect()
+        };
+        assert_eq!(clicks(&[Move, LeftPress, Move, LeftRelease]), [Click::Left]);
+        assert_eq!(clicks(&[RightPress, RightRelease]), [Click::Right]);
+        assert_eq!(clicks(&[LeftPress, RightPress, LeftRelease, RightRelease]), [Click::Chord]);
+        assert_eq!(clicks(&[LeftPress, RightPress, RightRelease, LeftRelease]), [Click::Chord]);
+        assert_eq!(clicks(&[RightPress, LeftPress, LeftRelease, RightRelease]), [
+            Click::Right,
+            Click::Chord
+        ]);
+        assert_eq!(clicks(&[MiddlePress, MiddleRelease]), [Click::Chord]);
+    }
+
+    #[test]
+    fn videos_round_trip_through_rawvf() {
+        let mut layout = Layout::new(9, 9);
+        for x in 0..9 {
+            layout.set(Coordinates::new(x, x), true, TileState::Covered);
+        }
+        let events = vec![
+            VideoEvent { time: 0.0, mouse: MouseEvent::Move, x: 3, y: 140 },
+            VideoEvent { time: 0.25, mouse: MouseEvent::LeftPress, x: 8, y: 136 },
+            VideoEvent { time: 0.5, mouse: MouseEvent::LeftRelease, x: 8, y: 136 },
+        ];
+        let video = Video {
+            layout,
+            player: String::from("Tester"),
+            timestamp: timestamp(1_700_000_000),
+            time: 0.5,
+            won: false,
+            bbbv: Some(10),
+            clicks: Some(Clicks { left: 1, right: 0, chords: 0 }),
+            flag_chord: true,
+            events,
+        };
+        let text = video.to_string();
+        assert!(text.contains("Timestamp: 2023-11-14 22:13:20"));
+        assert!(text.contains("Level: Custom"));
+        assert!(text.contains("0.500 lr 8 136 (1 9)"));
+        assert_eq!(Video::parse(&text), Ok(video));
+        let other = "RawVF_Version: Rev5\nLevel: Beginner\nBoard:\n*0\n00\nEvents:\n0.00 start\n\
+                     0.10 lc 20 4 (2 1)\n0.20 lr 20 4 (2 1)\n0.20 won\n";
+        let other = Video::parse(other).unwrap();
+        assert_eq!(other.events[1].coordinates(), Coordinates::new(1, 0));
+        assert!(other.won);
+        assert!((other.time - 0.2).abs() < f32::EPSILON);
+        assert_eq!((other.bbbv, other.clicks, other.flag_chord), (None, None, false));
+    }
+
+    #[test]
+    fn replays_are_verified_against_the_board_rules() {
+        let events = [
+            (1.0, LeftPress, 1, 1),
+            (1.0, LeftRelease, 1, 1),
+            (2.0, RightPress, 0, 0),
+            (2.0, RightRelease, 0, 0),
+            (3.0, LeftPress, 1, 1),
+            (3.0, LeftRelease, 1, 1),
+        ];
+        let mut video = Video {
+            layout: Layout::parse("*..\n...\n...\n").unwrap(),
+            player: String::new(),
+            timestamp: String::new(),
+            time: 3.0,
+            won: true,
+            bbbv: Some(1),
+            clicks: Some(Clicks { left: 1, right: 1, chords: 1 }),
+            flag_chord: false,
+            events: events
+                .map(|(time, mouse, x, y)| VideoEvent { time, mouse, x: x * 16 + 8, y: y * 16 + 8 })
+                .to_vec(),
+        };
+        assert_eq!(video.verify().map(|o| o.won), Ok(Some(true)));
+        video.time = 2.0;
+        video.clicks = Some(Clicks { left: 2, right: 1, chords: 0 });
+        assert_eq!(video.verify().unwrap_err().len(), 2);
+        video.events.truncate(4);
+        let outcome = video.simulate().unwrap();
+        assert_eq!((outcome.won, outcome.time, outcome.bbbv), (None, 2.0, 1));
+    }
+}